@@ -27,6 +27,7 @@ fn init(handle: InitHandle) {
     handle.add_class::<HybridUpdate01>();
     handle.add_class::<EndlessSpawnerSignals>();
     handle.add_class::<EndlessSpawnerHybrid>();
+    handle.add_class::<MultiMeshBenchmark>();
 }
 
 godot_init!(init);
@@ -1,5 +1,10 @@
 use gdnative::prelude::*;
 use gd_specs::*;
+use specs::prelude::*;
+use specs_engine::{Timer, TimerFinished, WorldMsgQueue};
+
+/// The tag of the repeating `Timer` that paces the despawning of the oldest spawns.
+const DESPAWN_TIMER_TAG: &str = "despawn_oldest";
 
 /// This example is intended to test the total performance of spawning, updating and despawning when using the Hybrid System with GDEntityHybrid and GDWorldHybrid 
 #[derive(NativeClass)]
@@ -9,6 +14,8 @@ pub struct EndlessSpawnerHybrid {
     spawns_per_second: f64,
     #[property(default = 10000)]
     max_spawns: i32,
+    // The number of the oldest spawns that are despawned per second once `max_spawns` has been reached.
+    // This is paced by a repeating `Timer` in the world, so it is only read when the world is set up.
     #[property(default = 100)]
     despawn_rate: i32,
     #[property(default = false)]
@@ -72,6 +79,11 @@ impl EndlessSpawnerHybrid {
                     self.bounding_box.size.height,
                 ));
                 world.insert_resource(specs_engine::WorldMsgQueue::<VSTransformSetMessage>::new());
+                if self.despawn_rate > 0 {
+                    world.world.create_entity()
+                        .with(Timer::repeating(1.0 / self.despawn_rate as f32, DESPAWN_TIMER_TAG))
+                        .build();
+                }
                 // Add a dispatcher that has all of the relevant systems
                 world.set_dispatcher(gd_specs::hybrid_sync_dispatcher(self.parallel, self.enable_velocity, self.enable_rotation, self.enable_scaling));
             }).expect("this should work correctly");
@@ -110,15 +122,43 @@ impl EndlessSpawnerHybrid {
         }
         if let Some(instance) = &self.world_instance {
             let instance = unsafe { instance.assume_safe() };
-            instance.map_mut(|world, owner| {
+            let despawn_ticks = instance.map_mut(|world, owner| {
                 world.run(owner, delta);
                 // If we attempt to parallelize the transformation it's still more optimized to 
                 // dispatch the VisualServer commands here
                 
+                let finished = world.world.fetch::<WorldMsgQueue<TimerFinished>>();
+                let mut despawn_ticks = 0;
+                while let Some(TimerFinished(_, tag)) = finished.pop() {
+                    if tag == DESPAWN_TIMER_TAG {
+                        despawn_ticks += 1;
+                    }
+                }
+                despawn_ticks
             }).expect("this should run successfully");
             if self.parallel {
                 self.update_visual_server_transform(owner);
             }
+            // The ticks while below `max_spawns` are dropped, so the oldest spawns are only despawned once the limit has been reached.
+            if self.spawns.len() >= self.max_spawns as usize {
+                self.despawn_oldest(despawn_ticks.min(self.spawns.len()));
+            }
+        }
+    }
+    /// Requests a despawn for the oldest spawns. The despawn pipeline will free the nodes once the world has run.
+    #[gdnative::profiled]
+    fn despawn_oldest(&mut self, num_despawns: usize) {
+        if let Some(instance) = &self.world_instance {
+            let instance = unsafe { instance.assume_safe() };
+            for node in self.spawns.drain(..num_despawns) {
+                let node = unsafe { node.assume_safe() };
+                let entity = node.cast::<Node2D>()
+                    .and_then(|node| node.cast_instance::<GDEntityHybrid>())
+                    .and_then(|gd_entity| gd_entity.map(|e, _| e.entity).expect("this should work"));
+                if let Some(entity) = entity {
+                    instance.map_mut(|world, _| world.despawn_entity(entity)).expect("this should work");
+                }
+            }
         }
     }
     #[export]
@@ -1,7 +1,13 @@
 use gdnative::prelude::*;
 use gdnative::api::Texture;
 use gd_specs::*;
-use specs_engine::SetVelocityIntent;
+use specs_engine::ActionMap;
+
+/// The actions used by this example, which can be rebound through `GDWorld.load_action_map`.
+const ACTION_MAP: &str = "
+axis move_x = ui_left, ui_right
+axis move_y = ui_up, ui_down
+";
 
 /// This example demonstrates handling player input within a bounding box based on Example01.
 /// In addition this demonstrates tagging of multiple entities 
@@ -48,7 +54,8 @@ impl Example02 {
                 if let Some(texture) = self.player_texture.clone() {
                     world.insert_resource(texture.clone());
                 }
-                world.set_dispatcher(gd_specs::example_2_dispatcher());
+                world.insert_resource(ActionMap::from_config(ACTION_MAP).expect("the action map should be valid"));
+                world.set_dispatcher(gd_specs::example_2_dispatcher(self.move_speed));
             }).expect("this should work correctly");
             self.world_instance = Some(gd_world.claim());
         }
@@ -59,21 +66,7 @@ impl Example02 {
         if let Some(instance) = &self.world_instance {
             let instance = unsafe { instance.assume_safe() };
             instance.map_mut(|world, owner| {
-                let input = Input::godot_singleton();
-                // Get the intent directly from the player.
-                let intent_direction = 
-                    Vector2::new(
-                        (input.get_action_strength("ui_right") - input.get_action_strength("ui_left"))as f32,
-                        (input.get_action_strength("ui_down") - input.get_action_strength("ui_up")) as f32
-                    ) * self.move_speed;
-                // POI #1
-                let entities = world.get_entities_with::<Player>();
-                for entity in entities {
-                    world.set_component_for_entity(
-                        entity,
-                        SetVelocityIntent { x: intent_direction.x, y: intent_direction.y }
-                    );
-                }
+                // POI #1: the `PlayerMovementSystem` turns the input into a `SetVelocityIntent` for each `Player`.
                 world.run(owner, delta);
             }).expect("this should run successfully");
         }
@@ -8,6 +8,7 @@ mod example_07;
 mod hybrid_update_01;
 mod endless_spawner_hybrid;
 mod endless_spawner_signal;
+mod multimesh_benchmark;
 
 pub use example_01::*;
 pub use example_02::*;
@@ -18,4 +19,5 @@ pub use example_06::*;
 pub use example_07::*;
 pub use hybrid_update_01::*;
 pub use endless_spawner_hybrid::*;
-pub use endless_spawner_signal::*;
\ No newline at end of file
+pub use endless_spawner_signal::*;
+pub use multimesh_benchmark::*;
\ No newline at end of file
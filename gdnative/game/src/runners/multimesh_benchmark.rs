@@ -0,0 +1,126 @@
+use gdnative::prelude::*;
+use gdnative::api::Texture;
+use gd_specs::*;
+use specs::prelude::*;
+use specs_engine::{Position, Rotation, Scale, Velocity, WorldCommand};
+use std::time::Instant;
+
+/// This example compares the cost of drawing many entities with the `MultiMeshRenderer` against the hybrid mode, where every entity has
+/// its own canvas item and transform update. Both modes spawn the same entities without any nodes and log the average time taken to
+/// run and flush the world every `report_interval` seconds.
+#[derive(NativeClass)]
+#[inherit(Node2D)]
+pub struct MultiMeshBenchmark {
+    #[property(default = true)]
+    use_multimesh: bool,
+    #[property(default = 10000)]
+    entity_count: i32,
+    #[property(default = 2.0)]
+    report_interval: f64,
+    #[property]
+    bounding_box: Rect2,
+    #[property]
+    world_path: NodePath,
+    #[property]
+    texture: Option<Ref<Texture>>,
+    elapsed: f64,
+    updates: u32,
+    update_seconds: f64,
+    world_instance: Option<Instance<GDWorldHybrid, Shared>>,
+}
+
+#[methods]
+impl MultiMeshBenchmark {
+    fn new(_: &Node2D) -> Self {
+        Self {
+            use_multimesh: true,
+            entity_count: 10000,
+            report_interval: 2.0,
+            bounding_box: Rect2::new(
+                Point2::new(0f32,0f32),
+                Size2::new(100f32, 100f32),
+            ),
+            world_path: NodePath::from_str("world"),
+            texture: None,
+            elapsed: 0.0,
+            updates: 0,
+            update_seconds: 0.0,
+            world_instance: None,
+        }
+    }
+
+    #[export]
+    pub fn _ready(&mut self, owner: &Node2D) {
+        let texture = match self.texture.clone() {
+            Some(texture) => texture,
+            None => {
+                log::error!("MultiMeshBenchmark requires a texture");
+                return;
+            }
+        };
+        if let Some(gd_world) = unsafe { owner.get_node_as_instance::<GDWorldHybrid>(self.world_path.to_string().as_str()) } {
+            gd_world.map_mut(|world, _| {
+                world.insert_resource(specs_engine::BoundingBox::new(
+                    self.bounding_box.origin.x,
+                    self.bounding_box.origin.y,
+                    self.bounding_box.size.width,
+                    self.bounding_box.size.height,
+                ));
+                let parent = owner.get_canvas_item();
+                if self.use_multimesh {
+                    world.insert_resource(MultiMeshRenderer::new(parent));
+                    world.set_dispatcher(gd_specs::multimesh_sync_dispatcher(true, false, false));
+                } else {
+                    world.set_dispatcher(gd_specs::hybrid_sync_dispatcher(false, true, false, false));
+                }
+                for i in 0..self.entity_count {
+                    // Spread the entities out with a fixed pattern so that both modes draw the same thing.
+                    let t = i as f32 * 0.618_034;
+                    let entity = world.world.create_entity()
+                        .with(Position {
+                            x: self.bounding_box.origin.x + (t % 1.0) * self.bounding_box.size.width,
+                            y: self.bounding_box.origin.y + ((t * 7.0) % 1.0) * self.bounding_box.size.height,
+                        })
+                        .with(Velocity { x: (t * 13.0 % 1.0 - 0.5) * 200.0, y: (t * 17.0 % 1.0 - 0.5) * 200.0 })
+                        .with(Rotation { radians: 0.0 })
+                        .with(Scale { x: 1.0, y: 1.0 })
+                        .build();
+                    let result = if self.use_multimesh {
+                        AddBatchedSprite::execute(&mut world.world, (entity, texture.clone(), None, Color::from_rgb(1.0, 1.0, 1.0)))
+                    } else {
+                        CreateSpriteCanvasItem::execute(&mut world.world, (entity, parent, texture.clone()))
+                    };
+                    if let Err(err) = result {
+                        log::error!("could not create the sprite for {:?}: {}", entity, err);
+                    }
+                }
+            }).expect("this should work correctly");
+            self.world_instance = Some(gd_world.claim());
+        }
+    }
+
+    #[export]
+    pub fn _process(&mut self, _: &Node2D, delta: f64) {
+        if let Some(instance) = &self.world_instance {
+            let instance = unsafe { instance.assume_safe() };
+            let start = Instant::now();
+            instance.map_mut(|world, owner| {
+                world.run(owner, delta);
+            }).expect("this should run successfully");
+            self.update_seconds += start.elapsed().as_secs_f64();
+            self.updates += 1;
+            self.elapsed += delta;
+            if self.elapsed >= self.report_interval {
+                log::info!(
+                    "{} mode: {} entities, {:.3} ms per update",
+                    if self.use_multimesh { "multimesh" } else { "hybrid" },
+                    self.entity_count,
+                    self.update_seconds * 1000.0 / self.updates as f64,
+                );
+                self.elapsed = 0.0;
+                self.updates = 0;
+                self.update_seconds = 0.0;
+            }
+        }
+    }
+}
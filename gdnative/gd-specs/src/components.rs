@@ -1,13 +1,14 @@
 use specs::prelude::*;
 use specs_derive::*;
 use gdnative::prelude::*;
-use gdnative::api::{ShaderMaterial, Texture};
+use gdnative::api::{ShaderMaterial, Texture, VisualServer};
+use gdnative::core_types::VariantType;
 
-#[derive(Debug, Default, Component, ToVariant)]
-#[storage(NullStorage)]
-pub struct Player;
+pub use specs_engine::Player;
 
 
+/// The fixed foreground and background colors used by the signal based examples, where they are read back through the `GDEntity`.
+/// Entities that are drawn through the `VisualServer` use `ShaderUniforms` instead, which works with any shader.
 #[derive(Debug, Component, ToVariant)]
 pub struct ShaderParams {
     pub (crate) fg: Color,
@@ -29,10 +30,171 @@ impl Default for ShaderParams {
     }
 }
 
+/// The value of a shader uniform that can be driven from the ECS.
+#[derive(Debug, Clone)]
+pub enum UniformValue {
+    Float(f32),
+    Vec2(Vector2),
+    Color(Color),
+    Texture(Option<Ref<Texture>>),
+}
+
+impl UniformValue {
+    /// Returns a value of the same type as the uniform, or `None` if the type cannot be driven from the ECS.
+    fn from_variant(variant_type: i64, value: &Variant) -> Option<Self> {
+        if variant_type == VariantType::F64 as i64 {
+            Some(Self::Float(value.try_to_f64().unwrap_or(0.0) as f32))
+        } else if variant_type == VariantType::Vector2 as i64 {
+            Some(Self::Vec2(value.try_to_vector2().unwrap_or_else(Vector2::zero)))
+        } else if variant_type == VariantType::Color as i64 {
+            Some(Self::Color(value.try_to_color().unwrap_or_else(|| Color::from_rgb(1.0, 1.0, 1.0))))
+        } else if variant_type == VariantType::Object as i64 {
+            Some(Self::Texture(value.try_to_object::<Texture>()))
+        } else {
+            None
+        }
+    }
+    /// Textures are compared by reference, so setting any texture counts as a change.
+    fn same_as(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Float(a), Self::Float(b)) => a == b,
+            (Self::Vec2(a), Self::Vec2(b)) => a == b,
+            (Self::Color(a), Self::Color(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl ToVariant for UniformValue {
+    fn to_variant(&self) -> Variant {
+        match self {
+            Self::Float(value) => value.to_variant(),
+            Self::Vec2(value) => value.to_variant(),
+            Self::Color(value) => value.to_variant(),
+            Self::Texture(value) => value.to_variant(),
+        }
+    }
+}
+
+/// The uniforms of the `CanvasItemShader` of an entity, which allows any shader to be driven from the ECS.
+/// Only the uniforms that are declared by the shader can be set, and only the uniforms that have changed are sent to the `VisualServer`.
+#[derive(Debug, Default, Component)]
+pub struct ShaderUniforms {
+    uniforms: Vec<(String, UniformValue, bool)>,
+}
+
+impl ShaderUniforms {
+    /// Reads every uniform that is declared by the shader of the material along with its current value.
+    /// Uniforms whose type is not supported by `UniformValue` are skipped.
+    pub fn from_material(material: TRef<ShaderMaterial>) -> Self {
+        let mut uniforms = Vec::new();
+        if let Some(shader) = material.shader() {
+            let vs = unsafe { VisualServer::godot_singleton() };
+            let shader_rid = unsafe { shader.assume_safe() }.get_rid();
+            for param in vs.shader_get_param_list(shader_rid).iter() {
+                let param = param.to_dictionary();
+                let name = param.get("name").to_string();
+                let variant_type = param.get("type").to_i64();
+                match UniformValue::from_variant(variant_type, &material.get_shader_param(name.as_str())) {
+                    Some(value) => uniforms.push((name, value, false)),
+                    None => log::trace!("skipping shader uniform {}", name),
+                }
+            }
+        }
+        Self { uniforms }
+    }
+    pub fn get(&self, name: &str) -> Option<&UniformValue> {
+        self.uniforms.iter().find(|(n, _, _)| n == name).map(|(_, value, _)| value)
+    }
+    /// Sets the uniform, which will be sent to the `VisualServer` if the value has changed.
+    /// Returns false if the shader does not declare the uniform or the value is not the same type.
+    pub fn set(&mut self, name: &str, value: UniformValue) -> bool {
+        match self.uniforms.iter_mut().find(|(n, _, _)| n == name) {
+            Some((_, current, changed)) if std::mem::discriminant(current) == std::mem::discriminant(&value) => {
+                if !current.same_as(&value) {
+                    *current = value;
+                    *changed = true;
+                }
+                true
+            },
+            Some(_) => {
+                log::warn!("shader uniform {} is not a {:?}", name, value);
+                false
+            },
+            None => false,
+        }
+    }
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.uniforms.iter().map(|(name, _, _)| name.as_str())
+    }
+    pub fn has_changes(&self) -> bool {
+        self.uniforms.iter().any(|(_, _, changed)| *changed)
+    }
+    /// Calls `f` with every uniform that has changed since the last call and marks them as unchanged.
+    pub (crate) fn apply_changes<F: FnMut(&str, &UniformValue)>(&mut self, mut f: F) {
+        for (name, value, changed) in self.uniforms.iter_mut().filter(|(_, _, changed)| *changed) {
+            f(name, value);
+            *changed = false;
+        }
+    }
+}
+
+/// Exposes the uniforms to GDScript as a dictionary of their names and values.
+impl ToVariant for ShaderUniforms {
+    fn to_variant(&self) -> Variant {
+        let dictionary = Dictionary::new();
+        for (name, value, _) in self.uniforms.iter() {
+            dictionary.insert(name.as_str(), value.to_variant());
+        }
+        dictionary.into_shared().to_variant()
+    }
+}
+
+/// The `ShaderUniforms` colors that can be animated by the `ShaderTweenSystem`. `Fg` and `Bg` animate the `fg` and `bg` uniforms.
+#[derive(Debug, Clone, Copy)]
+pub enum ShaderColorProperty {
+    Fg { from: Color, to: Color },
+    Bg { from: Color, to: Color },
+    Uniform { name: &'static str, from: Color, to: Color },
+}
+
+impl ShaderColorProperty {
+    pub fn uniform(&self) -> &'static str {
+        match self {
+            Self::Fg { .. } => "fg",
+            Self::Bg { .. } => "bg",
+            Self::Uniform { name, .. } => *name,
+        }
+    }
+}
+
+pub type ShaderTween = specs_engine::Tween<ShaderColorProperty>;
+
 /// Texture override can be used to temporarily override the texture of an object without fully replacing it.
-#[derive(Debug, Component)]
+/// The original `CanvasItemTexture` is restored once the override is removed. Timed overrides remove themselves once they run out,
+/// which is useful for hit flashes and power ups.
+#[derive(Debug)]
 pub struct TextureOverride {
     pub (crate) texture: Ref<Texture>,
+    pub (crate) remaining: Option<f32>,
+}
+
+/// Changes are tracked so that the `VSSyncTextures` only redraws the entities whose override has been added, changed or removed.
+impl Component for TextureOverride {
+    type Storage = FlaggedStorage<Self, DenseVecStorage<Self>>;
+}
+
+impl TextureOverride {
+    pub fn new(texture: Ref<Texture>) -> Self {
+        Self { texture, remaining: None }
+    }
+    pub fn timed(texture: Ref<Texture>, seconds: f32) -> Self {
+        Self { texture, remaining: Some(seconds) }
+    }
+    /// The seconds until a timed override is removed.
+    pub fn remaining(&self) -> Option<f32> {
+        self.remaining
+    }
 }
 
 /// This is the ECS version of the CanvasItem class used by Godot.
@@ -47,6 +209,9 @@ pub struct CanvasItem {
     // Represents the canvas where this exists.
     // This can be useful if you want to have a canvas item be possible swap canvases
     pub (crate) canvas_rid: Option<Rid>,
+    // True if the rid was created by `VisualServer::canvas_item_create()` rather than by a Godot `CanvasItem` node.
+    // Owned rids are freed by the despawn pipeline as Godot will never free them.
+    pub (crate) owned: bool,
 }
 
 // Optional: Implement drop for CanvasItem
@@ -55,6 +220,7 @@ pub struct CanvasItem {
 // As this approach bypasses the SceneTree entirely, it may be safer to also implement drop directly so that entity deletion will always result in freeing the Rid.
 // Note: This approach will be less performance than using a system to free them manually, but it may be safer depending upon your architecture.
 // This should be reviewed before using the same process in your own games.
+// This project instead marks rids created with `VisualServer::canvas_item_create()` as `owned` and frees them in the despawn pipeline (see `QueueGodotDespawnSystem`).
 // Link to Godot CanvasItem source code: https://github.com/godotengine/godot/blob/3.x/scene/2d/canvas_item.cpp#L1260 
 // impl Drop for CanvasItem {
 //     fn drop(&mut self) {
@@ -62,11 +228,26 @@ pub struct CanvasItem {
 //         vs.free_rid(self.rid);
 //     }
 // }
+/// The Godot node that represents this entity in the scene tree.
+/// When the entity is despawned, the node will be queued for deletion with `queue_free`.
+#[derive(Debug, Component)]
+pub struct GodotNode {
+    pub (crate) node: Ref<Node>,
+}
+
 pub struct MoveIntent {
     x: f32,
     y: f32,
 }
 
+/// An entity that is drawn by the `MultiMeshRenderer`. This is added by the `AddBatchedSprite` command, which assigns the batch and index.
+#[derive(Debug, Component)]
+pub struct BatchedSprite {
+    pub (crate) batch: usize,
+    pub (crate) index: usize,
+    pub color: Color,
+}
+
 #[derive(Debug, Component)]
 pub struct CanvasItemTexture {
     pub (crate) texture: Ref<Texture>,
@@ -77,10 +258,63 @@ pub struct CanvasItemShader {
 }
 
 pub fn register_components(world: &mut World) {
-    world.register::<Player>();
     world.register::<CanvasItem>();
     world.register::<CanvasItemTexture>();
+    world.register::<BatchedSprite>();
     world.register::<CanvasItemShader>();
     world.register::<ShaderParams>();
+    world.register::<ShaderUniforms>();
+    world.register::<ShaderTween>();
     world.register::<TextureOverride>();
-}
\ No newline at end of file
+    world.register::<GodotNode>();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn uniforms() -> ShaderUniforms {
+        ShaderUniforms {
+            uniforms: vec![
+                ("fg".to_owned(), UniformValue::Color(Color::from_rgb(1.0, 1.0, 1.0)), false),
+                ("speed".to_owned(), UniformValue::Float(1.0), false),
+            ],
+        }
+    }
+
+    fn changes(uniforms: &mut ShaderUniforms) -> Vec<String> {
+        let mut names = Vec::new();
+        uniforms.apply_changes(|name, _| names.push(name.to_owned()));
+        names
+    }
+
+    #[test]
+    pub fn test_set_uniform() {
+        let mut uniforms = uniforms();
+        // Uniforms that the shader does not declare cannot be set.
+        assert!(!uniforms.set("bg", UniformValue::Color(Color::from_rgb(0.0, 0.0, 0.0))));
+        assert!(uniforms.get("bg").is_none());
+        // The type of the uniform cannot be changed.
+        assert!(!uniforms.set("speed", UniformValue::Vec2(Vector2::new(1.0, 2.0))));
+        assert!(matches!(uniforms.get("speed"), Some(UniformValue::Float(speed)) if *speed == 1.0));
+        // Setting the current value is not a change.
+        assert!(uniforms.set("speed", UniformValue::Float(1.0)));
+        assert!(!uniforms.has_changes());
+
+        assert!(uniforms.set("speed", UniformValue::Float(2.0)));
+        assert!(uniforms.has_changes());
+        assert!(matches!(uniforms.get("speed"), Some(UniformValue::Float(speed)) if *speed == 2.0));
+    }
+
+    #[test]
+    pub fn test_apply_changes() {
+        let mut uniforms = uniforms();
+        assert!(changes(&mut uniforms).is_empty());
+        uniforms.set("fg", UniformValue::Color(Color::from_rgb(1.0, 0.0, 0.0)));
+        uniforms.set("speed", UniformValue::Float(3.0));
+        assert_eq!(changes(&mut uniforms), vec!["fg".to_owned(), "speed".to_owned()]);
+        // Each change is only applied once.
+        assert!(!uniforms.has_changes());
+        assert!(changes(&mut uniforms).is_empty());
+    }
+}
@@ -15,9 +15,10 @@ pub fn example_1_dispatcher<'a, 'b>(change_color:bool) -> Dispatcher<'a, 'b> {
         builder.build()
 }
 
-pub fn example_2_dispatcher<'a, 'b>() -> Dispatcher<'a, 'b> {
+pub fn example_2_dispatcher<'a, 'b>(move_speed: f32) -> Dispatcher<'a, 'b> {
     specs::DispatcherBuilder::new()
-        .with(SetVelocitySystem{}, "update_velocity", &[])
+        .with(PlayerMovementSystem::new(move_speed), "player_movement", &[])
+        .with(SetVelocitySystem{}, "update_velocity", &["player_movement"])
         .with(UpdatePositionWithBoundsSystem{}, "update_position", &[])
         .build()
 }
@@ -88,6 +89,8 @@ pub fn signal_sync_dispatcher<'a, 'b>(enable_velocity: bool, enable_rotation: bo
 
 pub fn hybrid_sync_dispatcher<'a, 'b>(parallel: bool, enable_velocity: bool, enable_rotation: bool, enable_scaling: bool) -> Dispatcher<'a, 'b> {
     let mut builder = specs::DispatcherBuilder::new();
+    builder.add(LifetimeSystem {}, "lifetime", &[]);
+    builder.add(TimerSystem {}, "timers", &[]);
     if enable_velocity {
             builder = builder.with(ChangeVelocityAtBounds{}, "update_velocity", &[])
             .with(UpdatePositionSystem{}, "update_position", &["update_velocity"]);
@@ -98,16 +101,59 @@ pub fn hybrid_sync_dispatcher<'a, 'b>(parallel: bool, enable_velocity: bool, ena
     if enable_scaling {
         builder.add(UpdateChildScaleSystem {}, "update_scale", &[]);
     }
+    builder.add(TransformTweenSystem {}, "transform_tween", &[]);
+    builder.add(ShaderTweenSystem {}, "shader_tween", &[]);
+    builder.add(SpriteAnimationSystem {}, "sprite_animation", &[]);
+    builder.add(TextureOverrideTimerSystem {}, "texture_override_timer", &[]);
         
     builder.add_barrier();
+    builder.add(CameraSystem {}, "camera", &[]);
+    builder.add(DebugDrawSystem::default(), "debug_draw", &[]);
+    builder.add(VSUpdateSpriteFrames::default(), "update_sprite_frames", &[]);
+    builder.add(DrawOrderSystem {}, "draw_order", &[]);
+    builder.add(VSUpdateDrawOrder::default(), "update_draw_order", &["draw_order"]);
+    builder.add(CullingSystem { margin: 32.0 }, "culling", &["camera"]);
+    builder.add(VSUpdateVisibility::default(), "update_visibility", &["culling"]);
     if parallel {
-        builder.add(VSUpdateTransformsParallel{}, "update_transforms", &[]);
+        builder.add(VSUpdateTransformsParallel{}, "update_transforms", &["culling"]);
     } else {
-        builder.add(VSUpdateTransforms{}, "update_transforms", &[]);
+        builder.add(VSUpdateTransforms{}, "update_transforms", &["culling"]);
     }
-        // .with(VSUpdateShaderParams{}, "update_shader_materials", &[])
+    builder.add(VSUpdateShaderUniforms{}, "update_shader_uniforms", &["culling"]);
+    builder.add_thread_local(VSSyncTextures::default());
+    builder.add(PropagateDespawnSystem {}, "propagate_despawn", &["lifetime"]);
+    builder.add(QueueGodotDespawnSystem {}, "queue_godot_despawn", &["propagate_despawn"]);
+    #[cfg(feature = "rapier")]
+    builder.add(RemoveDespawnedBodiesSystem {}, "remove_despawned_bodies", &["propagate_despawn"]);
+    #[cfg(feature = "rapier")]
+    builder.add(DespawnSystem {}, "despawn", &["queue_godot_despawn", "remove_despawned_bodies"]);
+    #[cfg(not(feature = "rapier"))]
+    builder.add(DespawnSystem {}, "despawn", &["queue_godot_despawn"]);
     builder.build()
 }
+/// The same as the `hybrid_sync_dispatcher`, except that the transforms are written into the batches of the `MultiMeshRenderer`.
+pub fn multimesh_sync_dispatcher<'a, 'b>(enable_velocity: bool, enable_rotation: bool, enable_scaling: bool) -> Dispatcher<'a, 'b> {
+    let mut builder = specs::DispatcherBuilder::new();
+    builder.add(LifetimeSystem {}, "lifetime", &[]);
+    if enable_velocity {
+        builder.add(ChangeVelocityAtBounds{}, "update_velocity", &[]);
+        builder.add(UpdatePositionSystem{}, "update_position", &["update_velocity"]);
+    }
+    if enable_rotation {
+        builder.add(UpdateChildRotationSystem {}, "update_rotation", &[]);
+    }
+    if enable_scaling {
+        builder.add(UpdateChildScaleSystem {}, "update_scale", &[]);
+    }
+    builder.add_barrier();
+    builder.add(UpdateMultiMeshSystem {}, "update_multimesh", &[]);
+    builder.add(PropagateDespawnSystem {}, "propagate_despawn", &["lifetime"]);
+    builder.add(QueueGodotDespawnSystem {}, "queue_godot_despawn", &["propagate_despawn"]);
+    builder.add(ReleaseBatchedSpritesSystem {}, "release_batched_sprites", &["propagate_despawn", "update_multimesh"]);
+    builder.add(DespawnSystem {}, "despawn", &["queue_godot_despawn", "release_batched_sprites"]);
+    builder.build()
+}
+
 // TODO: Demonstrate spawning spawning the equivalent entities directly with the VisualServer
 pub fn vs_sync_dispatcher<'a, 'b>(enable_velocity: bool, enable_rotation: bool, enable_scaling: bool) -> Dispatcher<'a, 'b> {
     let mut builder = specs::DispatcherBuilder::new();
@@ -123,9 +169,11 @@ pub fn vs_sync_dispatcher<'a, 'b>(enable_velocity: bool, enable_rotation: bool,
     }
         
     builder.with(RainbowColorSystem{}, "change_color", &[])
+        .with(SpriteAnimationSystem {}, "sprite_animation", &[])
         .with_barrier()
+        .with(VSUpdateSpriteFrames::default(), "update_sprite_frames", &[])
         .with(VSUpdateTransforms{}, "update_transforms", &[])
-        .with(VSUpdateShaderParams{}, "update_shader_materials", &[])
+        .with(VSUpdateShaderUniforms{}, "update_shader_uniforms", &[])
         // .with(CanvasItemSpawner {}, "spawner", &[])
         // .with(CanvasItemDespawner{}, "despawner", &[])
         .build()
@@ -16,6 +16,9 @@ pub struct GDEntity {
     // This is used to allow editor access from Godot as well as outputting the inforamtion
     #[property]
     pub components: Dictionary,
+    // The name of the current state of the entity's `StateMachine`, this can be used to drive animations.
+    #[property]
+    pub current_state: String,
     // This inner components are visible to the rust layer only.
     pub (crate) inner_components: HashMap<String, Variant>,
     // This is not visible to godot, but it will remain in Rust so that the entity can easily update it's properties
@@ -31,6 +34,7 @@ impl GDEntity {
             // Parent is the default node path
             world_path: NodePath::from_str(".."),
             components: Dictionary::new().into_shared(),
+            current_state: String::new(),
             inner_components: HashMap::new(),
             entity: None,
             sync_components: false,
@@ -163,7 +167,7 @@ impl GDEntity {
     #[gdnative::profiled]
     fn sync_internal_components(&mut self, world: &World) {
         use crate::{Player, ShaderParams};
-        use specs_engine::{Velocity,  Counter, SetVelocityIntent, StayInsideBounds};
+        use specs_engine::{Velocity,  Counter, SetVelocityIntent, StayInsideBounds, CurrentState};
         macro_rules! update_components {
             ($entity:ident, $($type:ident),*) => {
                 {
@@ -178,7 +182,7 @@ impl GDEntity {
             }
         }
         let entity = self.entity.expect("this should work");
-        update_components!(entity, Velocity, SetVelocityIntent, StayInsideBounds, Player, Counter, ShaderParams);
+        update_components!(entity, Velocity, SetVelocityIntent, StayInsideBounds, Player, Counter, ShaderParams, CurrentState);
     }
 
     /// Synchronizes the Entity to it's state in the world. If the entity is deleted, it will free itself from the scene tree.
@@ -188,6 +192,11 @@ impl GDEntity {
         if let Some(entity) = self.entity {
             if world.entities().is_alive(entity) {
                 self.sync_scene_tree(owner, world);
+                if let Some(state) = world.read_storage::<specs_engine::CurrentState>().get(entity) {
+                    if self.current_state != state.name {
+                        self.current_state = state.name.clone();
+                    }
+                }
                 //self.sync_internal_components(world);
                 
                 // In addition, we can expose certain information directly to Godot by adding variant support.
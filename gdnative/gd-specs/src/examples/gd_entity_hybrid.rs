@@ -80,7 +80,7 @@ impl GDEntityHybrid {
     #[inline]
     #[gdnative::profiled]
     fn sync_internal_components(&mut self, world: &World) {
-        use crate::{Player, ShaderParams};
+        use crate::{Player, ShaderUniforms};
         use specs_engine::{Position, Rotation, Scale, AngularVelocity, Velocity,  Counter, SetVelocityIntent, StayInsideBounds};
         macro_rules! update_components {
             ($entity:ident, $($type:ident),*) => {
@@ -96,7 +96,7 @@ impl GDEntityHybrid {
             }
         }
         let entity = self.entity.expect("this should work");
-        update_components!(entity, Position, Rotation, Scale, AngularVelocity, Velocity, SetVelocityIntent, StayInsideBounds, Player, Counter, ShaderParams);
+        update_components!(entity, Position, Rotation, Scale, AngularVelocity, Velocity, SetVelocityIntent, StayInsideBounds, Player, Counter, ShaderUniforms);
     }
 
     /// Synchronizes the Entity to it's state in the world. If the entity is deleted, it will free itself from the scene tree.
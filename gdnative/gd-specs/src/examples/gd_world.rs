@@ -1,8 +1,16 @@
 use gdnative::prelude::*;
+use gdnative::api::{InputEvent, InputEventMouse};
 use specs::prelude::*;
-use specs_engine::{Position, Velocity, SetVelocityIntent, StayInsideBounds, Counter};
+use specs_engine::{ActionMap, AnimationFinished, InputState, TimerFinished, TweenFinished, Position, Velocity, SetVelocityIntent, StayInsideBounds, Counter, Name, Tags, NameIndex, NameIndexSystem, FindByName, FindByTag, WorldCommand, WorldQuery};
 
-use crate::{GDEntity, Player, TextureOverride, ShaderParams};
+use crate::{EntityRef, GDEntity, Player, TextureOverride, ShaderParams};
+
+/// Converts a position in the viewport, such as the position of an `InputEventMouse`, to the canvas of the viewport that the node is in.
+pub (crate) fn viewport_to_world(node: TRef<Node>, position: Vector2) -> Vector2 {
+    node.get_viewport()
+        .and_then(|viewport| unsafe { viewport.assume_safe() }.canvas_transform().inverse())
+        .map_or(position, |transform| transform.transform_point(position.to_point()).to_vector())
+}
 
 /// This class wraps the specs world and allows it to easily pass the world instance between Godot and Specs.
 #[derive(NativeClass)]
@@ -11,6 +19,8 @@ use crate::{GDEntity, Player, TextureOverride, ShaderParams};
 pub struct GDWorld {
     pub world: World,
     dispatcher: Option<Dispatcher<'static, 'static>>,
+    // This is run after every update so that the `NameIndex` is always up to date regardless of the dispatcher.
+    name_index: NameIndexSystem,
 }
 
 #[methods]
@@ -25,11 +35,20 @@ impl GDWorld {
         let mut world = World::new();
         // At creation the GDWorld needs to create any required components, these resources can also be added later by the class that holds this.
         world.insert(specs_engine::Time::new());
+        world.insert(specs_engine::WorldMsgQueue::<specs_engine::DeferredCommand>::new());
+        world.insert(NameIndex::new());
+        world.insert(InputState::new());
+        world.insert(specs_engine::WorldMsgQueue::<AnimationFinished>::new());
+        world.insert(specs_engine::WorldMsgQueue::<TimerFinished>::new());
+        world.insert(specs_engine::WorldMsgQueue::<TweenFinished>::new());
         crate::components::register_components(&mut world);
         specs_engine::register_components(&mut world);
+        let mut name_index = NameIndexSystem::default();
+        System::setup(&mut name_index, &mut world);
         Self {
             world,
             dispatcher: None,
+            name_index,
         }
     }
     
@@ -69,7 +88,9 @@ impl GDWorld {
         }
     }
     /// Lets the game itself determine which systems this world operates when it needs to run.
-    pub fn set_dispatcher(&mut self, dispatcher: Dispatcher<'static, 'static>) {
+    /// The systems are set up with the world when the dispatcher is set, eg. to register the readers of component events.
+    pub fn set_dispatcher(&mut self, mut dispatcher: Dispatcher<'static, 'static>) {
+        dispatcher.setup(&mut self.world);
         self.dispatcher = Some(dispatcher)
     }
 
@@ -92,6 +113,76 @@ impl GDWorld {
         entities_with
     }
 
+    /// Finds the entity with the `Name`. Returns `null` if there is no entity with that name.
+    #[export]
+    pub fn find_by_name(&self, _: &Node, name: String) -> Variant {
+        if let Some(entity) = FindByName::query(&self.world, name) {
+            EntityRef::new(entity).emplace().owned_to_variant()
+        } else {
+            Variant::new()
+        }
+    }
+
+    /// Finds all of the entities that have the tag.
+    #[export]
+    pub fn find_by_tag(&self, _: &Node, tag: String) -> VariantArray {
+        let array = VariantArray::new();
+        for entity in FindByTag::query(&self.world, tag) {
+            array.push(EntityRef::new(entity).emplace().owned_to_variant());
+        }
+        array.into_shared()
+    }
+
+    /// Replaces the `ActionMap` used to fill the `InputState` with one loaded from the config. Returns false if the config is not valid.
+    #[export]
+    pub fn load_action_map(&mut self, _: &Node, config: String) -> bool {
+        match ActionMap::from_config(&config) {
+            Ok(action_map) => {
+                self.world.insert(action_map);
+                true
+            },
+            Err(err) => {
+                log::error!("could not load the action map: {}", err);
+                false
+            },
+        }
+    }
+
+    /// Binds the action to the names of actions in Godot's `InputMap`, replacing its previous bindings.
+    #[export]
+    pub fn rebind_action(&mut self, _: &Node, action: String, bindings: StringArray) {
+        if self.world.try_fetch::<ActionMap>().is_none() {
+            self.world.insert(ActionMap::new());
+        }
+        self.world.write_resource::<ActionMap>().bind_action(&action, bindings.read().iter().map(|binding| binding.to_string()).collect());
+    }
+
+    /// Returns the current `ActionMap` in the format read by `load_action_map` so that it can be saved.
+    #[export]
+    pub fn action_map_config(&self, _: &Node) -> String {
+        self.world.try_fetch::<ActionMap>().map(|action_map| action_map.to_config()).unwrap_or_default()
+    }
+
+    /// Records the position of the mouse in world space.
+    #[export]
+    pub fn _unhandled_input(&mut self, owner: TRef<Node>, event: Ref<InputEvent>) {
+        let event = unsafe { event.assume_safe() };
+        if let Some(mouse) = event.cast::<InputEventMouse>() {
+            let position = viewport_to_world(owner, mouse.position());
+            self.world.write_resource::<InputState>().set_pointer(position.x, position.y);
+        }
+    }
+
+    /// Fills the `InputState` from Godot's `Input` using the `ActionMap`. Nothing is updated if there is no `ActionMap`,
+    /// which allows the `InputState` to be set directly instead.
+    fn update_input(&mut self) {
+        if let Some(action_map) = self.world.try_fetch::<ActionMap>() {
+            let input = Input::godot_singleton();
+            let mut state = self.world.write_resource::<InputState>();
+            action_map.update(&mut state, |action| input.get_action_strength(action) as f32);
+        }
+    }
+
     // pub fn set_component_for_expr<C: Component>(&mut self, components: )
 
     /// Creates and entity from a GDEntity if possible.
@@ -119,6 +210,22 @@ impl GDWorld {
                 log::error!("velocity must be a vec2");
             }
         }
+        if let Some(name) = entity.inner_components.get("Name") {
+            if let Some(name) = name.try_to_string() {
+                log::trace!("with Name [{}]", name);
+                eb = eb.with(Name(name));
+            } else {
+                log::error!("name must be a string");
+            }
+        }
+        if let Some(tags) = entity.inner_components.get("Tags") {
+            if let Some(tags) = tags.try_to_array() {
+                log::trace!("with Tags");
+                eb = eb.with(Tags(tags.iter().filter_map(|tag| tag.try_to_string()).collect()));
+            } else {
+                log::error!("tags must be an array of strings");
+            }
+        }
         if entity.inner_components.contains_key("Player") {
             log::trace!("with Player");
             eb = eb.with(Player {});
@@ -146,7 +253,7 @@ impl GDWorld {
             // As the texture must come in as a variant, it will be necessary to convert it to an object and then to a `Texture`
             if let Some(texture) = texture_override.try_to_object::<Texture>() {
                 log::trace!("with TextureOverride");
-                eb = eb.with(TextureOverride { texture: texture.clone() });
+                eb = eb.with(TextureOverride::new(texture.clone()));
             } else {
                 log::error!("could not convert `TextureOverride` to texture");
             }
@@ -182,10 +289,15 @@ impl GDWorld {
                 time.delta = delta as f32;
                 time.total += delta as f32;
             }
+            self.update_input();
             // Run the world.
             dispatcher.run_now(&self.world);
+            // Any commands that were deferred by the systems can now be run with exclusive access to the world.
+            specs_engine::ApplyDeferredCommands::execute(&mut self.world, ());
             // Ensure that the world commits all of the changes from the systems.
             self.world.maintain();
+            // Removed names are only reported once the world has been maintained.
+            self.name_index.run_now(&self.world);
             // If everything can be assured to not attempt to access this until after the update is complete, such as by resolving during IDLE,
             // you can use emit signal
             owner.emit_signal("update_completed", &[]);
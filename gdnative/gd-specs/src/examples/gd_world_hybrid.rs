@@ -1,9 +1,10 @@
 use gdnative::prelude::*;
-use gdnative::api::ShaderMaterial;
+use gdnative::api::{Font, InputEvent, InputEventMouse, ShaderMaterial, TileMap};
 use specs::prelude::*;
-use specs_engine::{Position, Scale, Rotation, Velocity, SetVelocityIntent, StayInsideBounds, Counter, TreeRelationship, AngularVelocity};
+use specs_engine::{ActionMap, AnimationFinished, InputState, CameraView, DebugDraw, Position, Scale, Rotation, Velocity, SetVelocityIntent, StayInsideBounds, Counter, TreeRelationship, AngularVelocity, DespawnRequest, Lifetime, Name, Tags, NameIndex, NameIndexSystem, FindByName, FindByTag, PathRequest, PathNotFound, ViewRect, BoundingBox, CullBounds, CullingStats, TimerFinished, TweenFinished, WorldCommand, WorldQuery, WorldMsgQueue, YSort, ZIndex};
 
-use crate::{GDEntityHybrid, Player, CanvasItemShader, ShaderParams, TextureOverride};
+use crate::examples::gd_world::viewport_to_world;
+use crate::{tag_groups, SyncTagGroups, ApplyCameraView, CameraCanvas, CanvasItemTexture, DebugCanvas, FlushDebugDraw, EntityRef, FlushMultiMeshes, GDEntityHybrid, MultiMeshRenderer, GodotNode, GodotDespawnMessage, FreeGodotResources, LoadNavGridFromTileMap, Player, CanvasItemShader, ShaderUniforms, TextureOverride, UniformValue};
 
 /// This class wraps the specs world and allows it to easily pass the world instance between Godot and Specs.
 #[derive(NativeClass)]
@@ -12,6 +13,10 @@ use crate::{GDEntityHybrid, Player, CanvasItemShader, ShaderParams, TextureOverr
 pub struct GDWorldHybrid {
     pub world: World,
     dispatcher: Option<Dispatcher<'static, 'static>>,
+    // This is run after every update so that the `NameIndex` is always up to date regardless of the dispatcher.
+    name_index: NameIndexSystem,
+    // This is run after every update, before the `NameIndex`, so that the tags and node groups mirror each other.
+    tag_groups: SyncTagGroups,
 }
 
 #[methods]
@@ -26,11 +31,27 @@ impl GDWorldHybrid {
         let mut world = World::new();
         // At creation the GDWorldHybrid needs to create any required components, these resources can also be added later by the class that holds this.
         world.insert(specs_engine::Time::new());
+        world.insert(specs_engine::WorldMsgQueue::<specs_engine::DeferredCommand>::new());
+        world.insert(NameIndex::new());
+        world.insert(WorldMsgQueue::<GodotDespawnMessage>::new());
+        world.insert(WorldMsgQueue::<PathRequest>::new());
+        world.insert(WorldMsgQueue::<PathNotFound>::new());
+        world.insert(WorldMsgQueue::<AnimationFinished>::new());
+        world.insert(WorldMsgQueue::<TimerFinished>::new());
+        world.insert(WorldMsgQueue::<TweenFinished>::new());
+        world.insert(DebugDraw::new());
+        world.insert(InputState::new());
         crate::components::register_components(&mut world);
         specs_engine::register_components(&mut world);
+        let mut name_index = NameIndexSystem::default();
+        System::setup(&mut name_index, &mut world);
+        let mut tag_groups = SyncTagGroups::default();
+        System::setup(&mut tag_groups, &mut world);
         Self {
             world,
             dispatcher: None,
+            name_index,
+            tag_groups,
         }
     }
     
@@ -70,7 +91,9 @@ impl GDWorldHybrid {
         }
     }
     /// Lets the game itself determine which systems this world operates when it needs to run.
-    pub fn set_dispatcher(&mut self, dispatcher: Dispatcher<'static, 'static>) {
+    /// The systems are set up with the world when the dispatcher is set, eg. to register the readers of component events.
+    pub fn set_dispatcher(&mut self, mut dispatcher: Dispatcher<'static, 'static>) {
+        dispatcher.setup(&mut self.world);
         self.dispatcher = Some(dispatcher)
     }
 
@@ -93,6 +116,202 @@ impl GDWorldHybrid {
         entities_with
     }
 
+    /// Requests that the entity and all of its children are despawned the next time the world is run.
+    pub fn despawn_entity(&mut self, entity: Entity) {
+        if let Err(err) = self.world.write_storage::<DespawnRequest>().insert(entity, DespawnRequest) {
+            log::warn!("could not despawn {:?}: {}", entity, err);
+        }
+    }
+
+    /// Finds the entity with the `Name`. Returns `null` if there is no entity with that name.
+    #[export]
+    pub fn find_by_name(&self, _: &Node, name: String) -> Variant {
+        if let Some(entity) = FindByName::query(&self.world, name) {
+            EntityRef::new(entity).emplace().owned_to_variant()
+        } else {
+            Variant::new()
+        }
+    }
+
+    /// Finds all of the entities that have the tag.
+    #[export]
+    pub fn find_by_tag(&self, _: &Node, tag: String) -> VariantArray {
+        let array = VariantArray::new();
+        for entity in FindByTag::query(&self.world, tag) {
+            array.push(EntityRef::new(entity).emplace().owned_to_variant());
+        }
+        array.into_shared()
+    }
+
+    /// Replaces the `NavGrid` with one built from the used cells of the tilemap. If `used_cells_walkable` is false, the used cells are treated as walls.
+    /// Returns false if the nav grid could not be built.
+    #[export]
+    pub fn load_nav_grid(&mut self, _: &Node, tilemap: Ref<TileMap>, used_cells_walkable: bool) -> bool {
+        match LoadNavGridFromTileMap::execute(&mut self.world, (tilemap, used_cells_walkable)) {
+            Ok(()) => true,
+            Err(err) => {
+                log::error!("could not load the nav grid: {}", err);
+                false
+            }
+        }
+    }
+
+    /// Applies the view of the current `Camera` to the canvas that the canvas item is drawn in.
+    /// Returns false if the canvas item is not inside of the scene tree.
+    #[export]
+    pub fn set_camera_canvas(&mut self, _: &Node, canvas_item: Ref<CanvasItem>) -> bool {
+        let canvas_item = unsafe { canvas_item.assume_safe() };
+        match CameraCanvas::from_canvas_item(canvas_item) {
+            Some(camera_canvas) => {
+                let size = unsafe { camera_canvas.viewport.assume_safe() }.size();
+                if self.world.try_fetch::<CameraView>().is_none() {
+                    self.world.insert(CameraView::new(size.x, size.y));
+                }
+                self.world.insert(camera_canvas);
+                true
+            },
+            None => {
+                log::error!("the camera canvas item must be inside of the scene tree");
+                false
+            },
+        }
+    }
+
+    /// Enables or disables drawing the `DebugDraw` over the game. The debug canvas item is created in the canvas of the viewport the first
+    /// time this is enabled. Returns false if the world is not inside of the scene tree.
+    #[export]
+    pub fn set_debug_draw(&mut self, owner: &Node, enabled: bool) -> bool {
+        if enabled && self.world.try_fetch::<DebugCanvas>().is_none() {
+            let canvas = owner.get_viewport()
+                .and_then(|viewport| unsafe { viewport.assume_safe() }.world_2d())
+                .map(|world_2d| unsafe { world_2d.assume_safe() }.canvas());
+            match canvas {
+                Some(canvas) => self.world.insert(DebugCanvas::new(canvas)),
+                None => {
+                    log::error!("the debug draw can only be enabled inside of the scene tree");
+                    return false;
+                },
+            }
+        }
+        self.world.write_resource::<DebugDraw>().set_enabled(enabled);
+        if !enabled {
+            // Clear whatever was drawn by the last frame.
+            FlushDebugDraw::execute(&mut self.world, ());
+        }
+        true
+    }
+
+    /// Sets the font used to draw the text in the `DebugDraw`.
+    #[export]
+    pub fn set_debug_font(&mut self, _: &Node, font: Ref<Font>) {
+        match self.world.try_fetch_mut::<DebugCanvas>() {
+            Some(mut canvas) => canvas.set_font(font),
+            None => log::error!("the debug draw must be enabled before setting the font"),
+        }
+    }
+
+    #[export]
+    pub fn _exit_tree(&mut self, _: &Node) {
+        if let Some(mut canvas) = self.world.remove::<DebugCanvas>() {
+            canvas.free();
+        }
+        if let Some(mut renderer) = self.world.remove::<MultiMeshRenderer>() {
+            renderer.free();
+        }
+    }
+
+    /// Whether the groups of every node are checked for changes after each update, so that `add_to_group` and `remove_from_group` calls
+    /// made from GDScript are reflected in the entity's `Tags`. This is disabled by default as it visits every node each frame.
+    /// Changes to `Tags` are always applied to the groups.
+    #[export]
+    pub fn set_group_polling(&mut self, _: &Node, enabled: bool) {
+        self.tag_groups.poll_groups = enabled;
+    }
+
+    /// The number of entities that were culled by the last update.
+    #[export]
+    pub fn culled_count(&self, _: &Node) -> i64 {
+        self.world.try_fetch::<CullingStats>().map_or(0, |stats| stats.culled as i64)
+    }
+
+    /// Replaces the `ActionMap` used to fill the `InputState` with one loaded from the config. Returns false if the config is not valid.
+    #[export]
+    pub fn load_action_map(&mut self, _: &Node, config: String) -> bool {
+        match ActionMap::from_config(&config) {
+            Ok(action_map) => {
+                self.world.insert(action_map);
+                true
+            },
+            Err(err) => {
+                log::error!("could not load the action map: {}", err);
+                false
+            },
+        }
+    }
+
+    /// Binds the action to the names of actions in Godot's `InputMap`, replacing its previous bindings.
+    #[export]
+    pub fn rebind_action(&mut self, _: &Node, action: String, bindings: StringArray) {
+        if self.world.try_fetch::<ActionMap>().is_none() {
+            self.world.insert(ActionMap::new());
+        }
+        self.world.write_resource::<ActionMap>().bind_action(&action, bindings.read().iter().map(|binding| binding.to_string()).collect());
+    }
+
+    /// Returns the current `ActionMap` in the format read by `load_action_map` so that it can be saved.
+    #[export]
+    pub fn action_map_config(&self, _: &Node) -> String {
+        self.world.try_fetch::<ActionMap>().map(|action_map| action_map.to_config()).unwrap_or_default()
+    }
+
+    /// Records the position of the mouse in world space. The `CameraView` is used if there is one, as it is applied directly to the canvas
+    /// instead of through the viewport.
+    #[export]
+    pub fn _unhandled_input(&mut self, owner: TRef<Node>, event: Ref<InputEvent>) {
+        let event = unsafe { event.assume_safe() };
+        if let Some(mouse) = event.cast::<InputEventMouse>() {
+            let position = mouse.position();
+            let view = self.world.try_fetch::<CameraView>().map(|view| *view);
+            let (x, y) = match view {
+                Some(view) => view.screen_to_world(position.x, position.y),
+                None => {
+                    let position = viewport_to_world(owner, position);
+                    (position.x, position.y)
+                }
+            };
+            self.world.write_resource::<InputState>().set_pointer(x, y);
+        }
+    }
+
+    /// Fills the `InputState` from Godot's `Input` using the `ActionMap`. Nothing is updated if there is no `ActionMap`,
+    /// which allows the `InputState` to be set directly instead.
+    fn update_input(&mut self) {
+        if let Some(action_map) = self.world.try_fetch::<ActionMap>() {
+            let input = Input::godot_singleton();
+            let mut state = self.world.write_resource::<InputState>();
+            action_map.update(&mut state, |action| input.get_action_strength(action) as f32);
+        }
+    }
+
+    /// Without a `CameraView` the view is the viewport with the default canvas transform, otherwise it is the area that the view makes visible.
+    /// The `CameraSystem` refines this after moving the camera, but the rect is derived here as well so that it is never stale.
+    fn update_view_rect(&mut self, owner: TRef<Node>) {
+        let view = self.world.try_fetch::<CameraView>().map(|view| *view);
+        let rect = match view {
+            // A bounding box cannot be empty, which the viewport can be while the window is minimized.
+            Some(view) if view.screen_width > 0.0 && view.screen_height > 0.0 && view.zoom > 0.0 => view.visible_rect(),
+            Some(_) => return,
+            None => match owner.get_viewport() {
+                Some(viewport) => {
+                    let size = unsafe { viewport.assume_safe() }.size();
+                    BoundingBox::new(0.0, 0.0, size.x.max(1.0), size.y.max(1.0))
+                }
+                None => return,
+            },
+        };
+        self.world.insert(ViewRect(rect));
+    }
+
     // pub fn set_component_for_expr<C: Component>(&mut self, components: )
     pub fn create_entities_child(&mut self, parent_entity: Entity, child_node: TRef<Node2D>) -> Entity{
         let mut eb = self.world.create_entity();
@@ -106,6 +325,7 @@ impl GDWorldHybrid {
         eb = eb.with(crate::components::CanvasItem {
             rid: child_node.get_canvas_item(),
             canvas_rid: Some(child_node.get_canvas()),
+            owned: false,
         });
         log::trace!("with canvas_item {:?} and parent {:?}", &child_node.get_canvas_item(), child_node.get_canvas());
         eb = eb.with(TreeRelationship {
@@ -134,7 +354,10 @@ impl GDWorldHybrid {
         eb = eb.with(crate::components::CanvasItem {
             rid: entity_owner.get_canvas_item(),
             canvas_rid: Some(entity_owner.get_canvas()),
+            owned: false,
         });
+        // The node is freed along with the entity, so any child nodes will be freed by Godot.
+        eb = eb.with(GodotNode { node: entity_owner.upcast::<Node>().claim() });
         log::trace!("with canvas_item {:?} and parent {:?}", &entity_owner.get_canvas_item(), entity_owner.get_canvas());
         eb = eb.with(TreeRelationship {
             parent: None,
@@ -161,6 +384,41 @@ impl GDWorldHybrid {
                 log::error!("velocity must be a vec2");
             }
         }
+        if let Some(name) = entity.inner_components.get("Name") {
+            if let Some(name) = name.try_to_string() {
+                log::trace!("with Name [{}]", name);
+                eb = eb.with(Name(name));
+            } else {
+                log::error!("name must be a string");
+            }
+        }
+        // Tags mirror the groups of the node, any tags that are only set on the entity are added to the node's groups.
+        // Later changes on either side are synced by the `SyncTagGroups` system.
+        let mut tags = tag_groups(entity_owner.upcast::<Node>());
+        if let Some(entity_tags) = entity.inner_components.get("Tags") {
+            if let Some(entity_tags) = entity_tags.try_to_array() {
+                for tag in entity_tags.iter().filter_map(|tag| tag.try_to_string()) {
+                    if !tags.contains(tag.as_str()) {
+                        entity_owner.add_to_group(tag.as_str(), false);
+                        tags.insert(tag);
+                    }
+                }
+            } else {
+                log::error!("tags must be an array of strings");
+            }
+        }
+        if !tags.is_empty() {
+            log::trace!("with Tags");
+            eb = eb.with(Tags(tags));
+        }
+        if let Some(lifetime) = entity.inner_components.get("Lifetime") {
+            if let Some(remaining) = lifetime.try_to_f64() {
+                log::trace!("with Lifetime [{}]", remaining);
+                eb = eb.with(Lifetime { remaining: remaining as f32 });
+            } else {
+                log::error!("lifetime must be a float");
+            }
+        }
         if entity.inner_components.contains_key("Player") {
             log::trace!("with Player");
             eb = eb.with(Player {});
@@ -170,6 +428,27 @@ impl GDWorldHybrid {
             fg.b = 0.0;
         }
 
+        if let Some(z_index) = entity.inner_components.get("ZIndex") {
+            if let Some(z_index) = z_index.try_to_i64() {
+                log::trace!("with ZIndex [{}]", z_index);
+                eb = eb.with(ZIndex(z_index as i32));
+            } else {
+                log::error!("z index must be an integer");
+            }
+        }
+        if let Some(cull_bounds) = entity.inner_components.get("CullBounds") {
+            if let Some(half_extents) = cull_bounds.try_to_vector2() {
+                log::trace!("with CullBounds [{}, {}]", half_extents.x, half_extents.y);
+                eb = eb.with(CullBounds { half_width: half_extents.x, half_height: half_extents.y });
+            } else {
+                log::error!("cull bounds must be a Vector2 of the half extents");
+            }
+        }
+        if entity.inner_components.contains_key("YSort") {
+            log::trace!("with YSort");
+            eb = eb.with(YSort);
+        }
+
         if entity.inner_components.contains_key("SetVelocityIntent") {
             log::trace!("with SetVelocityIntent");
             eb = eb.with(SetVelocityIntent { x: 0f32, y: 0f32 });
@@ -188,18 +467,31 @@ impl GDWorldHybrid {
             // As the texture must come in as a variant, it will be necessary to convert it to an object and then to a `Texture`
             if let Some(texture) = texture_override.try_to_object::<Texture>() {
                 log::trace!("with TextureOverride");
-                eb = eb.with(TextureOverride { texture: texture.clone() });
+                eb = eb.with(TextureOverride::new(texture.clone()));
             } else {
                 log::error!("could not convert `TextureOverride` to texture");
             }
         }
 
+        // The texture of the sprite is kept so that it can be restored after a `TextureOverride` is removed.
+        if let Some(texture) = entity_owner.get_node("sprite")
+            .and_then(|node| unsafe { node.assume_safe() }.cast::<Sprite>())
+            .and_then(|sprite| sprite.texture()) {
+            log::trace!("with CanvasItemTexture");
+            eb = eb.with(CanvasItemTexture { texture });
+        }
         // If it has a sprite attached and the sprite has a material, set the shader parameters.
         if let Some(node) = entity_owner.get_node("sprite") {
             let node = unsafe { node.assume_unique() };
             if let Ok(canvas_item) = node.try_cast::<CanvasItem>() {
                 if let Some(material) = canvas_item.material() {
                     if let Ok(material) = material.try_cast::<ShaderMaterial>() {
+                        // The colors are only set if the shader declares `fg` and `bg` uniforms.
+                        let mut uniforms = ShaderUniforms::from_material(unsafe { material.assume_safe() });
+                        uniforms.set("fg", UniformValue::Color(fg));
+                        uniforms.set("bg", UniformValue::Color(bg));
+                        log::trace!("with ShaderUniforms");
+                        eb = eb.with(uniforms);
                         eb = eb.with(CanvasItemShader {
                             material: material.clone()
                         });
@@ -207,8 +499,6 @@ impl GDWorldHybrid {
                     } else {
                         log::error!("material is not compatible with ShaderMaterial")
                     }
-                    log::trace!("with ShaderParams");
-                    eb = eb.with(ShaderParams::new(fg, bg));
                 }
             } else {
                 log::error!("child named `sprite` cannot be cast to `Sprite`")
@@ -248,10 +538,23 @@ impl GDWorldHybrid {
                 time.delta = delta as f32;
                 time.total += delta as f32;
             }
+            self.update_view_rect(owner);
+            self.update_input();
             // Run the world.
             dispatcher.run_now(&self.world);
+            // Any commands that were deferred by the systems can now be run with exclusive access to the world.
+            specs_engine::ApplyDeferredCommands::execute(&mut self.world, ());
+            // Godot resources of despawned entities must be freed on the main thread.
+            FreeGodotResources::execute(&mut self.world, ());
+            // The canvas transform must also be set on the main thread.
+            ApplyCameraView::execute(&mut self.world, ());
+            FlushMultiMeshes::execute(&mut self.world, ());
+            FlushDebugDraw::execute(&mut self.world, ());
             // Ensure that the world commits all of the changes from the systems.
             self.world.maintain();
+            self.tag_groups.run_now(&self.world);
+            // Removed names are only reported once the world has been maintained.
+            self.name_index.run_now(&self.world);
             // If everything can be assured to not attempt to access this until after the update is complete, such as by resolving during IDLE,
             // you can use emit signal
             owner.emit_signal("update_completed", &[]);
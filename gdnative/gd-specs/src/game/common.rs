@@ -1,4 +1,5 @@
 use gdnative::prelude::*;
+use gdnative::api::{Texture, TileMap, VisualServer};
 use specs::prelude::*;
 use specs_engine::*;
 use crate::ComponentInfo;
@@ -25,3 +26,83 @@ impl WorldCommand for CreateEntityFromComponentInfo {
     }
 }
 
+
+/// Replaces the `NavGrid` resource with one that covers the used rect of the `TileMap`. `Args` are `(tilemap, used_cells_walkable)`,
+/// if `used_cells_walkable` is true only the used cells can be walked on, otherwise the used cells are treated as walls.
+/// Only square cells are supported and the tilemap is expected to be unscaled.
+pub struct LoadNavGridFromTileMap {}
+
+impl WorldCommand for LoadNavGridFromTileMap {
+    type Args = (Ref<TileMap>, bool);
+    type Output = Result<(), &'static str>;
+    fn execute(world: &mut World, args: Self::Args) -> Self::Output {
+        let (tilemap, used_cells_walkable) = args;
+        let tilemap = unsafe { tilemap.assume_safe_if_sane() }.ok_or("the tilemap has been freed")?;
+        let rect = tilemap.get_used_rect();
+        let (min_x, min_y) = (rect.origin.x as i32, rect.origin.y as i32);
+        let (width, height) = (rect.size.width as i32, rect.size.height as i32);
+        if width <= 0 || height <= 0 {
+            return Err("the tilemap does not have any used cells");
+        }
+        let cell_size = tilemap.cell_size();
+        if cell_size.x != cell_size.y {
+            log::warn!("the tilemap cells are not square, the nav grid will use a cell size of {}", cell_size.x);
+        }
+        let position = tilemap.position();
+        let mut grid = NavGrid::new(width, height, cell_size.x)
+            .with_origin(position.x + min_x as f32 * cell_size.x, position.y + min_y as f32 * cell_size.y);
+        if used_cells_walkable {
+            for x in 0..width {
+                for y in 0..height {
+                    grid.set_walkable((x, y), false);
+                }
+            }
+        }
+        for cell in tilemap.get_used_cells().iter() {
+            if let Some(cell) = cell.try_to_vector2() {
+                grid.set_walkable((cell.x as i32 - min_x, cell.y as i32 - min_y), used_cells_walkable);
+            }
+        }
+        world.insert(grid);
+        Ok(())
+    }
+}
+
+/// Creates a canvas item that draws the texture centered on the entity, without a Godot node. `Args` are `(entity, parent, texture)`
+/// where `parent` is the canvas or canvas item to draw in. The canvas item is owned by the entity and is freed by the despawn pipeline.
+/// This must be executed on the main thread.
+pub struct CreateSpriteCanvasItem {}
+
+impl WorldCommand for CreateSpriteCanvasItem {
+    type Args = (Entity, Rid, Ref<Texture>);
+    type Output = Result<(), &'static str>;
+    fn execute(world: &mut World, args: Self::Args) -> Self::Output {
+        let (entity, parent, texture) = args;
+        if !world.is_alive(entity) {
+            return Err("entity is not alive");
+        }
+        let vs = unsafe { VisualServer::godot_singleton() };
+        let (texture_rid, size) = {
+            let texture = unsafe { texture.assume_safe() };
+            (texture.get_rid(), texture.get_size())
+        };
+        let rid = vs.canvas_item_create();
+        vs.canvas_item_set_parent(rid, parent);
+        vs.canvas_item_add_texture_rect(
+            rid,
+            Rect2::new(Point2::new(-size.x / 2.0, -size.y / 2.0), Size2::new(size.x, size.y)),
+            texture_rid,
+            false,
+            Color::from_rgb(1.0, 1.0, 1.0),
+            false,
+            Rid::new(),
+        );
+        world.write_storage::<crate::components::CanvasItem>()
+            .insert(entity, crate::components::CanvasItem { rid, canvas_rid: Some(parent), owned: true })
+            .expect("the entity should be alive");
+        world.write_storage::<CanvasItemTexture>()
+            .insert(entity, CanvasItemTexture { texture })
+            .expect("the entity should be alive");
+        Ok(())
+    }
+}
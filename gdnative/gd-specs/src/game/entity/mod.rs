@@ -19,8 +19,23 @@ pub struct EntityRef {
     entity: Entity
 }
 
+impl EntityRef {
+    pub fn new(entity: Entity) -> Self {
+        Self { entity }
+    }
+    pub fn entity(&self) -> Entity {
+        self.entity
+    }
+}
+
 #[methods]
-impl EntityRef {}
+impl EntityRef {
+    /// The index of the entity, this can be used to compare entities in GDScript.
+    #[export]
+    pub fn id(&self, _: &Reference) -> u32 {
+        self.entity.id()
+    }
+}
 
 pub struct ComponentInfo {
     pub (crate) position: Option<Position>,
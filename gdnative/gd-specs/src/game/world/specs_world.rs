@@ -11,14 +11,23 @@ use specs_engine::*;
 /// The goal for this is that the GameWorld
 pub struct SpecsWorld {
     pub (crate) world: World,
+    // This is run after every update so that the `NameIndex` is always up to date regardless of the dispatcher.
+    name_index: NameIndexSystem,
 }
 
 
 
 impl SpecsWorld {
     fn new() -> Self {
+        let mut world = World::new();
+        world.insert(WorldMsgQueue::<DeferredCommand>::new());
+        world.insert(WorldMsgQueue::<TimerFinished>::new());
+        world.insert(NameIndex::new());
+        let mut name_index = NameIndexSystem::default();
+        System::setup(&mut name_index, &mut world);
         Self {
-            world: World::new(),
+            world,
+            name_index,
         }
     }
     /// Allows for passing a closure that can register
@@ -78,6 +87,16 @@ impl SpecsWorld {
         C::execute(&mut self.world, args)
     }
 
+    /// Finds the entity with the `Name`. The `NameIndexSystem` must have run since the name was set.
+    pub fn find_by_name(&self, name: &str) -> Option<Entity> {
+        self.query::<FindByName>(name.to_owned())
+    }
+
+    /// Finds all of the entities with the tag in their `Tags`. The `NameIndexSystem` must have run since the tags were set.
+    pub fn find_by_tag(&self, tag: &str) -> Vec<Entity> {
+        self.query::<FindByTag>(tag.to_owned())
+    }
+
     pub fn get_entities_with<C: Component>(&mut self) -> Vec<Entity> {
         let entities = self.world.entities();
         let storage = self.world.read_storage::<C>();
@@ -101,8 +120,12 @@ impl SpecsWorld {
     pub fn run_with<'a, 'b>(&mut self, dispatcher: &mut Dispatcher<'a, 'b>, delta: f64) {
             // Run the world.
             dispatcher.run_now(&self.world);
+            // Any commands that were deferred by the systems can now be run with exclusive access to the world.
+            ApplyDeferredCommands::execute(&mut self.world, ());
             // Ensure that the world commits all of the changes from the systems.
             self.world.maintain();
+            // Removed names are only reported once the world has been maintained.
+            self.name_index.run_now(&self.world);
             // If everything can be assured to not attempt to access this until after the update is complete, such as by resolving during IDLE,
             // you can use emit signal
             // owner.emit_signal("update_completed", &[]);
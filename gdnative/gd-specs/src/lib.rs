@@ -25,4 +25,5 @@ pub fn init(handle: InitHandle) {
     handle.add_class::<GDEntity>();
     handle.add_class::<GDWorldHybrid>();
     handle.add_class::<GDEntityHybrid>();
+    handle.add_class::<EntityRef>();
 }
\ No newline at end of file
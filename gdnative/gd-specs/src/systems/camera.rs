@@ -0,0 +1,51 @@
+//! This applies the `CameraView` from `specs_engine` to a canvas, which gives scenes that are drawn directly with the `VisualServer` a camera
+//! without needing a `Camera2D` node.
+use gdnative::prelude::*;
+use gdnative::api::{VisualServer, Viewport};
+use specs::prelude::*;
+use specs_engine::{CameraView, WorldCommand};
+
+/// The viewport and canvas that the `CameraView` is applied to.
+pub struct CameraCanvas {
+    pub (crate) viewport: Ref<Viewport>,
+    pub (crate) canvas: Rid,
+}
+
+impl CameraCanvas {
+    /// Uses the canvas and viewport that the canvas item is drawn in. Returns `None` if the canvas item is not inside of the scene tree.
+    pub fn from_canvas_item(canvas_item: TRef<CanvasItem>) -> Option<Self> {
+        let viewport = canvas_item.get_viewport()?;
+        Some(Self { viewport, canvas: canvas_item.get_canvas() })
+    }
+}
+
+/// Updates the screen size of the `CameraView` and sets it as the canvas transform of the `CameraCanvas`. This must be executed on the main thread.
+/// Nothing is applied unless both resources have been added to the world.
+pub struct ApplyCameraView {}
+
+impl WorldCommand for ApplyCameraView {
+    type Args = ();
+    type Output = ();
+    fn execute(world: &mut World, _: Self::Args) -> Self::Output {
+        let canvas = match world.try_fetch::<CameraCanvas>() {
+            Some(canvas) => canvas,
+            None => return,
+        };
+        let mut view = match world.try_fetch_mut::<CameraView>() {
+            Some(view) => view,
+            None => return,
+        };
+        let viewport = match unsafe { canvas.viewport.assume_safe_if_sane() } {
+            Some(viewport) => viewport,
+            None => {
+                log::warn!("the camera viewport has been freed");
+                return;
+            }
+        };
+        let size = viewport.size();
+        view.set_screen_size(size.x, size.y);
+        let [a, b, c, d, x, y] = view.transform();
+        let vs = unsafe { VisualServer::godot_singleton() };
+        vs.viewport_set_canvas_transform(viewport.get_viewport_rid(), canvas.canvas, Transform2D::new(a, b, c, d, x, y));
+    }
+}
@@ -0,0 +1,96 @@
+//! This draws the shapes in the `DebugDraw` from `specs_engine` onto a canvas item that is drawn above everything else.
+use gdnative::prelude::*;
+use gdnative::api::{Font, VisualServer};
+use specs::prelude::*;
+use specs_engine::{DebugColor, DebugDraw, DebugShape, WorldCommand};
+
+// The number of lines used to draw the outline of a circle.
+const CIRCLE_SEGMENTS: usize = 24;
+
+/// The canvas item that the `DebugDraw` is drawn on. The canvas item is owned by this resource and must be freed with `free`.
+pub struct DebugCanvas {
+    pub (crate) rid: Rid,
+    pub (crate) font: Option<Ref<Font>>,
+}
+
+impl DebugCanvas {
+    /// Creates a canvas item in the canvas that is drawn with the highest z index, so that it is drawn on top of the game.
+    pub fn new(canvas: Rid) -> Self {
+        let vs = unsafe { VisualServer::godot_singleton() };
+        let rid = vs.canvas_item_create();
+        vs.canvas_item_set_parent(rid, canvas);
+        vs.canvas_item_set_z_as_relative_to_parent(rid, false);
+        vs.canvas_item_set_z_index(rid, VisualServer::CANVAS_ITEM_Z_MAX);
+        Self { rid, font: None }
+    }
+    /// Text is only drawn once a font has been set.
+    pub fn set_font(&mut self, font: Ref<Font>) {
+        self.font = Some(font);
+    }
+    pub fn free(&mut self) {
+        let vs = unsafe { VisualServer::godot_singleton() };
+        vs.free_rid(self.rid);
+    }
+}
+
+/// Clears the `DebugCanvas` and draws every shape that has been pushed into the `DebugDraw` since the last flush.
+/// This must be executed on the main thread once per frame. The `DebugDraw` is emptied even if there is no `DebugCanvas`.
+pub struct FlushDebugDraw {}
+
+impl WorldCommand for FlushDebugDraw {
+    type Args = ();
+    type Output = ();
+    fn execute(world: &mut World, _: Self::Args) -> Self::Output {
+        let shapes = match world.try_fetch::<DebugDraw>() {
+            Some(debug_draw) => debug_draw.drain(),
+            None => return,
+        };
+        let canvas = match world.try_fetch::<DebugCanvas>() {
+            Some(canvas) => canvas,
+            None => return,
+        };
+        let vs = unsafe { VisualServer::godot_singleton() };
+        vs.canvas_item_clear(canvas.rid);
+        for (shape, color) in shapes {
+            let color = to_color(color);
+            match shape {
+                DebugShape::Line { from, to, width } => {
+                    vs.canvas_item_add_line(canvas.rid, Vector2::new(from.0, from.1), Vector2::new(to.0, to.1), color, width as f64, false);
+                },
+                DebugShape::Rect { x, y, width, height, filled: true } => {
+                    vs.canvas_item_add_rect(canvas.rid, Rect2::new(Point2::new(x, y), Size2::new(width, height)), color);
+                },
+                DebugShape::Rect { x, y, width, height, filled: false } => {
+                    let corners = [
+                        Vector2::new(x, y),
+                        Vector2::new(x + width, y),
+                        Vector2::new(x + width, y + height),
+                        Vector2::new(x, y + height),
+                    ];
+                    for i in 0..corners.len() {
+                        vs.canvas_item_add_line(canvas.rid, corners[i], corners[(i + 1) % corners.len()], color, 1.0, false);
+                    }
+                },
+                DebugShape::Circle { x, y, radius } => {
+                    let point = |i: usize| {
+                        let angle = i as f32 / CIRCLE_SEGMENTS as f32 * std::f32::consts::TAU;
+                        Vector2::new(x + angle.cos() * radius, y + angle.sin() * radius)
+                    };
+                    for i in 0..CIRCLE_SEGMENTS {
+                        vs.canvas_item_add_line(canvas.rid, point(i), point(i + 1), color, 1.0, false);
+                    }
+                },
+                DebugShape::Text { x, y, text } => {
+                    if let Some(font) = &canvas.font {
+                        let font = unsafe { font.assume_safe() };
+                        font.draw(canvas.rid, Vector2::new(x, y), text, color, -1, Color::from_rgba(0.0, 0.0, 0.0, 0.0));
+                    }
+                },
+            }
+        }
+    }
+}
+
+fn to_color(color: DebugColor) -> Color {
+    Color::from_rgba(color[0], color[1], color[2], color[3])
+}
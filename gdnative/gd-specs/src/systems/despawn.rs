@@ -0,0 +1,64 @@
+//! This contains the Godot specific part of the despawn pipeline from `specs_engine`.
+//! Neither the `VisualServer` nor the `SceneTree` should be modified from the dispatcher's worker threads, so the resources are
+//! collected into a queue that is emptied on the main thread by `FreeGodotResources` after the dispatcher has run.
+use gdnative::prelude::*;
+use gdnative::api::VisualServer;
+use specs::prelude::*;
+use specs_engine::{DespawnRequest, WorldCommand, WorldMsgQueue};
+
+use crate::components::{CanvasItem, GodotNode};
+
+pub enum GodotDespawnMessage {
+    FreeRid(Rid),
+    QueueFree(Ref<Node>),
+}
+
+/// Collects the Godot resources owned by entities that have requested a despawn.
+/// This must run after the `PropagateDespawnSystem` and before the `DespawnSystem`.
+// Note: The `WorldMsgQueue<GodotDespawnMessage>` resource MUST be added to the simulation for this system to work.
+pub struct QueueGodotDespawnSystem {}
+
+impl <'a> System <'a> for QueueGodotDespawnSystem {
+    type SystemData = (
+        ReadExpect<'a, WorldMsgQueue<GodotDespawnMessage>>,
+        ReadStorage<'a, DespawnRequest>,
+        ReadStorage<'a, CanvasItem>,
+        ReadStorage<'a, GodotNode>,
+    );
+    fn run(&mut self, data: Self::SystemData) {
+        let (queue, despawn_requests, canvas_items, nodes) = data;
+        for (canvas_item, _) in (&canvas_items, &despawn_requests).join() {
+            // Rids that belong to a Godot node are freed by the node itself.
+            if canvas_item.owned {
+                queue.push(GodotDespawnMessage::FreeRid(canvas_item.rid));
+            }
+        }
+        for (node, _) in (&nodes, &despawn_requests).join() {
+            queue.push(GodotDespawnMessage::QueueFree(node.node.clone()));
+        }
+    }
+}
+
+/// Frees all of the resources in the `WorldMsgQueue<GodotDespawnMessage>`. This must be executed on the main thread.
+pub struct FreeGodotResources {}
+
+impl WorldCommand for FreeGodotResources {
+    type Args = ();
+    type Output = ();
+    fn execute(world: &mut World, _: Self::Args) -> Self::Output {
+        if let Some(queue) = world.try_fetch::<WorldMsgQueue<GodotDespawnMessage>>() {
+            let vs = unsafe { VisualServer::godot_singleton() };
+            while let Some(msg) = queue.pop() {
+                match msg {
+                    GodotDespawnMessage::FreeRid(rid) => vs.free_rid(rid),
+                    GodotDespawnMessage::QueueFree(node) => {
+                        // The node may have already been freed by its parent.
+                        if let Some(node) = unsafe { node.assume_safe_if_sane() } {
+                            node.queue_free();
+                        }
+                    },
+                }
+            }
+        }
+    }
+}
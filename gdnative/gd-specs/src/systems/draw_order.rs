@@ -0,0 +1,44 @@
+//! This applies the `DrawOrder` from `specs_engine` to the canvas items of the entities.
+use gdnative::api::VisualServer;
+use specs::prelude::*;
+use specs_engine::DrawOrder;
+
+/// Sets the z index and draw index of the canvas items whose `DrawOrder` has changed since the last run, so that the `VisualServer` is
+/// only called for the entities that have actually moved in the draw order.
+/// This system must be set up with `Dispatcher::setup` / `System::setup` before it is run.
+#[derive(Default)]
+pub struct VSUpdateDrawOrder {
+    reader: Option<ReaderId<ComponentEvent>>,
+}
+
+impl <'a> System <'a> for VSUpdateDrawOrder {
+    type SystemData = (
+        ReadStorage<'a, DrawOrder>,
+        ReadStorage<'a, crate::components::CanvasItem>,
+    );
+    fn setup(&mut self, world: &mut World) {
+        Self::SystemData::setup(world);
+        self.reader = Some(WriteStorage::<DrawOrder>::fetch(world).register_reader());
+    }
+    fn run(&mut self, data: Self::SystemData) {
+        let (draw_orders, canvas_items) = data;
+        let reader = self.reader.as_mut().expect("VSUpdateDrawOrder::setup has not been called");
+        let mut changed = BitSet::new();
+        for event in draw_orders.channel().read(reader) {
+            match event {
+                ComponentEvent::Inserted(id) | ComponentEvent::Modified(id) => {
+                    changed.add(*id);
+                },
+                ComponentEvent::Removed(_) => {},
+            }
+        }
+        if changed.is_empty() {
+            return;
+        }
+        let vs = unsafe { VisualServer::godot_singleton() };
+        for (draw_order, canvas_item, _) in (&draw_orders, &canvas_items, &changed).join() {
+            vs.canvas_item_set_z_index(canvas_item.rid, draw_order.z_index() as i64);
+            vs.canvas_item_set_draw_index(canvas_item.rid, draw_order.draw_index() as i64);
+        }
+    }
+}
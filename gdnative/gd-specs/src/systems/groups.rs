@@ -0,0 +1,96 @@
+//! This keeps the `Tags` of each entity that has a `GodotNode` mirrored with the groups of the node.
+use gdnative::prelude::*;
+use specs::prelude::*;
+use specs_engine::{SmallSet, Tags};
+
+use crate::components::GodotNode;
+
+/// Returns the groups of the node that are used as tags. Groups starting with `_` are used internally by Godot and are ignored.
+pub fn tag_groups(node: TRef<Node>) -> SmallSet<String> {
+    node.get_groups().iter()
+        .filter_map(|group| group.try_to_string())
+        .filter(|group| !group.starts_with('_'))
+        .collect()
+}
+
+fn same_tags(a: &SmallSet<String>, b: &SmallSet<String>) -> bool {
+    a.len() == b.len() && a.iter().all(|tag| b.contains(tag.as_str()))
+}
+
+/// Syncs the `Tags` and node groups in both directions. Changes to `Tags` are read from the component events and the difference is applied
+/// to the node's groups, then, if `poll_groups` is set (off by default), the groups of every node are compared against its `Tags` to pick up any
+/// `add_to_group` or `remove_from_group` calls made from Godot. Removing the `Tags` of a living entity removes the node from all of its groups.
+/// As this modifies nodes, it must either be run on the main thread or added to the dispatcher with `add_thread_local`.
+/// This system must be set up with `System::setup` before it is run.
+pub struct SyncTagGroups {
+    pub poll_groups: bool,
+    tag_reader: Option<ReaderId<ComponentEvent>>,
+}
+
+impl Default for SyncTagGroups {
+    fn default() -> Self {
+        Self { poll_groups: false, tag_reader: None }
+    }
+}
+
+impl <'a> System <'a> for SyncTagGroups {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, Tags>,
+        ReadStorage<'a, GodotNode>,
+    );
+    fn setup(&mut self, world: &mut World) {
+        Self::SystemData::setup(world);
+        self.tag_reader = Some(WriteStorage::<Tags>::fetch(world).register_reader());
+    }
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, mut tags, nodes) = data;
+        let tag_reader = self.tag_reader.as_mut().expect("SyncTagGroups::setup has not been called");
+        let mut changed = Vec::new();
+        for event in tags.channel().read(tag_reader) {
+            let id = match event {
+                ComponentEvent::Inserted(id) | ComponentEvent::Modified(id) | ComponentEvent::Removed(id) => *id,
+            };
+            changed.push(id);
+        }
+        for id in changed {
+            let entity = entities.entity(id);
+            if !entities.is_alive(entity) {
+                continue;
+            }
+            let node = match nodes.get(entity).and_then(|node| unsafe { node.node.assume_safe_if_sane() }) {
+                Some(node) => node,
+                None => continue,
+            };
+            let empty = SmallSet::new();
+            let entity_tags = tags.get(entity).map_or(&empty, |entity_tags| &entity_tags.0);
+            let groups = tag_groups(node);
+            for group in groups.iter().filter(|group| !entity_tags.contains(group.as_str())) {
+                node.remove_from_group(group.as_str());
+            }
+            for tag in entity_tags.iter().filter(|tag| !groups.contains(tag.as_str())) {
+                node.add_to_group(tag.as_str(), false);
+            }
+        }
+        if !self.poll_groups {
+            return;
+        }
+        for (entity, node) in (&entities, &nodes).join() {
+            let node = match unsafe { node.node.assume_safe_if_sane() } {
+                Some(node) => node,
+                None => continue,
+            };
+            let groups = tag_groups(node);
+            match tags.get(entity) {
+                Some(entity_tags) if same_tags(&entity_tags.0, &groups) => {},
+                None if groups.is_empty() => {},
+                _ => {
+                    log::trace!("{:?} groups changed", entity);
+                    tags.insert(entity, Tags(groups)).expect("the entity should be alive");
+                },
+            }
+        }
+        // The groups already match the tags that were just changed, so the events do not need to be applied again.
+        for _ in tags.channel().read(tag_reader) {}
+    }
+}
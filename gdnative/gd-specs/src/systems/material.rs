@@ -1,6 +1,7 @@
 use specs::prelude::*;
-use specs_engine::{Time, Counter};
-use crate::ShaderParams;
+use gdnative::prelude::*;
+use specs_engine::{Time, Counter, TweenFinished, WorldMsgQueue, lerp};
+use crate::{ShaderParams, ShaderColorProperty, ShaderTween, ShaderUniforms, UniformValue};
 
 /// This system uses system time and converts the FG shader param.
 /// Note: This would be much faster to implement in a shader, but this is made to demonstrate how you can easily feed
@@ -8,15 +9,26 @@ use crate::ShaderParams;
 pub struct RainbowColorSystem {}
 
 impl <'a> System <'a> for RainbowColorSystem {
-    type SystemData = (ReadExpect<'a, Time>, WriteStorage<'a, ShaderParams>);
+    type SystemData = (ReadExpect<'a, Time>, WriteStorage<'a, ShaderParams>, WriteStorage<'a, ShaderUniforms>);
     fn run(&mut self, data: Self::SystemData) {
 
-        let (time, mut shader_params) = data;
+        let (time, mut shader_params, mut shader_uniforms) = data;
+        // These are pretty arbitrary, they just allow the color to change while still being visible on a black background
+        let fg = Color::from_rgb(
+            (time.total * 0.5).sin().max(0.2),
+            ((time.total * 2.0) + 0.5).sin().max(0.2),
+            (time.total + 1.0).sin().max(0.2),
+        );
         for params in (&mut shader_params).join() {
-            // These are pretty arbitrary, they just allow the color to change while still being visible on a black background
-            params.fg.r = (time.total * 0.5).sin().max(0.2);
-            params.fg.g = ((time.total * 2.0) + 0.5).sin().max(0.2);
-            params.fg.b = (time.total + 1.0).sin().max(0.2);
+            params.fg.r = fg.r;
+            params.fg.g = fg.g;
+            params.fg.b = fg.b;
+        }
+        for uniforms in (&mut shader_uniforms).join() {
+            if let Some(UniformValue::Color(current)) = uniforms.get("fg") {
+                let color = Color::from_rgba(fg.r, fg.g, fg.b, current.a);
+                uniforms.set("fg", UniformValue::Color(color));
+            }
         }
     }
 }
@@ -36,4 +48,42 @@ impl <'a> System <'a> for ColorBasedOnCountSystem {
             params.fg.b = ((value % 1000) as f32).sin().max(0.2);
         }
     }
-}
\ No newline at end of file
+}
+
+/// Applies `ShaderTween`s to the `ShaderUniforms` of the entity. Tweens of uniforms that the shader does not declare do nothing.
+// Note: The `WorldMsgQueue<TweenFinished>` resource MUST be added to the simulation for this system to work.
+pub struct ShaderTweenSystem {}
+
+impl <'a> System <'a> for ShaderTweenSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Time>,
+        ReadExpect<'a, WorldMsgQueue<TweenFinished>>,
+        WriteStorage<'a, ShaderTween>,
+        WriteStorage<'a, ShaderUniforms>,
+    );
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, time, finished_queue, mut tweens, mut shader_uniforms) = data;
+        fn lerp_color(from: Color, to: Color, t: f32) -> Color {
+            Color::from_rgba(
+                lerp(from.r, to.r, t),
+                lerp(from.g, to.g, t),
+                lerp(from.b, to.b, t),
+                lerp(from.a, to.a, t),
+            )
+        }
+        for (entity, tween, uniforms) in (&entities, &mut tweens, &mut shader_uniforms).join() {
+            let finished = tween.advance(time.delta, |property, t| {
+                let color = match *property {
+                    ShaderColorProperty::Fg { from, to }
+                    | ShaderColorProperty::Bg { from, to }
+                    | ShaderColorProperty::Uniform { from, to, .. } => lerp_color(from, to, t),
+                };
+                uniforms.set(property.uniform(), UniformValue::Color(color));
+            });
+            if finished {
+                finished_queue.push(TweenFinished(entity, tween.tag));
+            }
+        }
+    }
+}
@@ -1,4 +1,20 @@
+mod camera;
+mod debug_draw;
+mod despawn;
+mod draw_order;
+mod groups;
 mod material;
+mod multimesh;
+mod sprite;
+mod texture;
 mod visual_server_systems;
+pub use camera::*;
+pub use debug_draw::*;
+pub use despawn::*;
+pub use draw_order::*;
+pub use groups::*;
 pub use material::*;
+pub use multimesh::*;
+pub use sprite::*;
+pub use texture::*;
 pub use visual_server_systems::*;
@@ -0,0 +1,232 @@
+//! This contains the `MultiMeshRenderer`, which draws every entity that shares a texture and material with a single `VisualServer` multimesh.
+//! The hybrid and server-centric paths make one `canvas_item_set_transform` call per entity, while this makes one
+//! `multimesh_set_as_bulk_array` call per batch. The transforms are written into the batches by the `UpdateMultiMeshSystem`, which does not
+//! call into Godot and can run on any thread, and are sent to the `VisualServer` by `FlushMultiMeshes` on the main thread.
+use gdnative::prelude::*;
+use gdnative::api::{Material, QuadMesh, Texture, VisualServer};
+use specs::prelude::*;
+use specs_engine::{DespawnRequest, Position, Rotation, Scale, WorldCommand};
+
+use crate::components::BatchedSprite;
+
+// Each instance is a 2D transform (8 floats) followed by a color (4 floats).
+const FLOATS_PER_INSTANCE: usize = 12;
+
+/// A multimesh and the canvas item that draws it, shared by every entity with the same texture and material.
+pub struct MultiMeshBatch {
+    texture: Ref<Texture>,
+    material: Option<Ref<Material>>,
+    // The mesh must be kept alive for as long as the multimesh uses it.
+    _mesh: Ref<QuadMesh>,
+    multimesh: Rid,
+    canvas_item: Rid,
+    // The entity at each index, this is used to move the last entity into the gap when an entity is removed.
+    entities: Vec<Entity>,
+    // The buffer covers every instance that the multimesh will be allocated with, the instances after `entities` are hidden.
+    buffer: Vec<f32>,
+    // The number of instances allocated in the multimesh, this is only increased so that the multimesh is rarely reallocated.
+    allocated: usize,
+}
+
+impl MultiMeshBatch {
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+}
+
+/// The batches used by the `BatchedSprite`s. Batches are created as new combinations of texture and material are added and are only freed
+/// by `free`, so the renderer must be freed before the world is dropped. `GDWorldHybrid` does this when it exits the tree.
+pub struct MultiMeshRenderer {
+    parent: Rid,
+    batches: Vec<MultiMeshBatch>,
+}
+
+impl MultiMeshRenderer {
+    /// `parent` is the canvas or canvas item that the batches are drawn in.
+    pub fn new(parent: Rid) -> Self {
+        Self { parent, batches: Vec::new() }
+    }
+    pub fn batches(&self) -> &[MultiMeshBatch] {
+        &self.batches
+    }
+    /// Returns the index of the batch for the texture and material, creating it if it does not exist. This must be called on the main thread.
+    fn batch_for(&mut self, texture: Ref<Texture>, material: Option<Ref<Material>>) -> usize {
+        let texture_rid = unsafe { texture.assume_safe() }.get_rid();
+        let material_rid = material.as_ref().map(|material| unsafe { material.assume_safe() }.get_rid());
+        let existing = self.batches.iter().position(|batch| {
+            unsafe { batch.texture.assume_safe() }.get_rid() == texture_rid &&
+                batch.material.as_ref().map(|material| unsafe { material.assume_safe() }.get_rid()) == material_rid
+        });
+        if let Some(index) = existing {
+            return index;
+        }
+        let vs = unsafe { VisualServer::godot_singleton() };
+        let size = unsafe { texture.assume_safe() }.get_size();
+        let mesh = QuadMesh::new();
+        // A `QuadMesh` is built for 3D where y points up, so it must be flipped to be drawn the right way up in 2D.
+        mesh.set_size(Vector2::new(size.x, -size.y));
+        let mesh = mesh.into_shared();
+        let multimesh = vs.multimesh_create();
+        vs.multimesh_set_mesh(multimesh, unsafe { mesh.assume_safe() }.get_rid());
+        let canvas_item = vs.canvas_item_create();
+        vs.canvas_item_set_parent(canvas_item, self.parent);
+        vs.canvas_item_add_multimesh(canvas_item, multimesh, texture_rid, Rid::new());
+        if let Some(material_rid) = material_rid {
+            vs.canvas_item_set_material(canvas_item, material_rid);
+        }
+        self.batches.push(MultiMeshBatch {
+            texture,
+            material,
+            _mesh: mesh,
+            multimesh,
+            canvas_item,
+            entities: Vec::new(),
+            buffer: Vec::new(),
+            allocated: 0,
+        });
+        self.batches.len() - 1
+    }
+    /// Frees the rids of every batch. This must be called on the main thread.
+    pub fn free(&mut self) {
+        let vs = unsafe { VisualServer::godot_singleton() };
+        for batch in self.batches.drain(..) {
+            vs.free_rid(batch.canvas_item);
+            vs.free_rid(batch.multimesh);
+        }
+    }
+}
+
+/// Adds the entity to the batch for the texture and material. `Args` are `(entity, texture, material, color)`.
+/// This must be executed on the main thread as new batches create their rids immediately.
+// Note: The `MultiMeshRenderer` resource MUST be added to the world for this command to work.
+pub struct AddBatchedSprite {}
+
+impl WorldCommand for AddBatchedSprite {
+    type Args = (Entity, Ref<Texture>, Option<Ref<Material>>, Color);
+    type Output = Result<(), &'static str>;
+    fn execute(world: &mut World, args: Self::Args) -> Self::Output {
+        let (entity, texture, material, color) = args;
+        if !world.is_alive(entity) {
+            return Err("entity is not alive");
+        }
+        if world.read_storage::<BatchedSprite>().contains(entity) {
+            return Err("entity is already batched");
+        }
+        let mut renderer = world.try_fetch_mut::<MultiMeshRenderer>().ok_or("the MultiMeshRenderer resource does not exist")?;
+        let batch_index = renderer.batch_for(texture, material);
+        let batch = &mut renderer.batches[batch_index];
+        let index = batch.entities.len();
+        batch.entities.push(entity);
+        if batch.entities.len() * FLOATS_PER_INSTANCE > batch.buffer.len() {
+            // The buffer grows ahead of the multimesh, which is reallocated to match it by `FlushMultiMeshes`.
+            batch.buffer.resize(batch.entities.len().next_power_of_two() * FLOATS_PER_INSTANCE, 0.0);
+        }
+        drop(renderer);
+        world.write_storage::<BatchedSprite>()
+            .insert(entity, BatchedSprite { batch: batch_index, index, color })
+            .expect("the entity should be alive");
+        Ok(())
+    }
+}
+
+/// Writes the transform and color of every `BatchedSprite` into its batch.
+// Note: The `MultiMeshRenderer` resource MUST be added to the simulation for this system to work.
+pub struct UpdateMultiMeshSystem {}
+
+impl <'a> System <'a> for UpdateMultiMeshSystem {
+    type SystemData = (
+        WriteExpect<'a, MultiMeshRenderer>,
+        ReadStorage<'a, BatchedSprite>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, Rotation>,
+        ReadStorage<'a, Scale>,
+    );
+    fn run(&mut self, data: Self::SystemData) {
+        let (mut renderer, sprites, positions, rotations, scales) = data;
+        for (sprite, position, rotation, scale) in (&sprites, &positions, rotations.maybe(), scales.maybe()).join() {
+            let radians = rotation.map_or(0.0, |rotation| rotation.radians);
+            let (scale_x, scale_y) = scale.map_or((1.0, 1.0), |scale| (scale.x, scale.y));
+            let (sin, cos) = radians.sin_cos();
+            let start = sprite.index * FLOATS_PER_INSTANCE;
+            let instance = &mut renderer.batches[sprite.batch].buffer[start..start + FLOATS_PER_INSTANCE];
+            // The rows of the transform as expected by `multimesh_set_as_bulk_array`, followed by the color.
+            instance.copy_from_slice(&[
+                cos * scale_x, -sin * scale_y, 0.0, position.x,
+                sin * scale_x, cos * scale_y, 0.0, position.y,
+                sprite.color.r, sprite.color.g, sprite.color.b, sprite.color.a,
+            ]);
+        }
+    }
+}
+
+/// Removes the entities that have requested a despawn from their batches. The last entity in the batch is moved into the gap so that
+/// the batch can be drawn without any holes. This must run after the `PropagateDespawnSystem` and before the `DespawnSystem`.
+// Note: The `MultiMeshRenderer` resource MUST be added to the simulation for this system to work.
+pub struct ReleaseBatchedSpritesSystem {}
+
+impl <'a> System <'a> for ReleaseBatchedSpritesSystem {
+    type SystemData = (
+        Entities<'a>,
+        WriteExpect<'a, MultiMeshRenderer>,
+        ReadStorage<'a, DespawnRequest>,
+        WriteStorage<'a, BatchedSprite>,
+    );
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, mut renderer, despawn_requests, mut sprites) = data;
+        let released: Vec<Entity> = (&entities, &sprites, &despawn_requests).join().map(|(entity, _, _)| entity).collect();
+        for entity in released {
+            let sprite = sprites.remove(entity).expect("the sprite was just joined");
+            let batch = &mut renderer.batches[sprite.batch];
+            batch.entities.swap_remove(sprite.index);
+            // The buffer keeps its size, the instance that is left at the end is hidden by `FlushMultiMeshes`.
+            let last = batch.entities.len() * FLOATS_PER_INSTANCE;
+            if sprite.index < batch.entities.len() {
+                let moved = batch.entities[sprite.index];
+                let start = sprite.index * FLOATS_PER_INSTANCE;
+                batch.buffer.copy_within(last..last + FLOATS_PER_INSTANCE, start);
+                if let Some(moved) = sprites.get_mut(moved) {
+                    moved.index = sprite.index;
+                }
+            }
+        }
+    }
+}
+
+/// Sends the buffer of every batch to the `VisualServer`. This must be executed on the main thread.
+/// Nothing is done if the `MultiMeshRenderer` has not been added to the world.
+pub struct FlushMultiMeshes {}
+
+impl WorldCommand for FlushMultiMeshes {
+    type Args = ();
+    type Output = ();
+    fn execute(world: &mut World, _: Self::Args) -> Self::Output {
+        if let Some(mut renderer) = world.try_fetch_mut::<MultiMeshRenderer>() {
+            let vs = unsafe { VisualServer::godot_singleton() };
+            for batch in renderer.batches.iter_mut() {
+                let len = batch.entities.len();
+                let capacity = batch.buffer.len() / FLOATS_PER_INSTANCE;
+                if capacity > batch.allocated {
+                    // Allocating clears the multimesh, which is fine as the whole buffer is sent below.
+                    batch.allocated = capacity;
+                    vs.multimesh_allocate(
+                        batch.multimesh,
+                        batch.allocated as i64,
+                        VisualServer::MULTIMESH_TRANSFORM_2D,
+                        VisualServer::MULTIMESH_COLOR_FLOAT,
+                        VisualServer::MULTIMESH_CUSTOM_DATA_NONE,
+                    );
+                }
+                if batch.allocated == 0 {
+                    continue;
+                }
+                // The bulk array must cover every allocated instance, which the buffer always does, so it is only copied into the array.
+                // The unused instances are hidden by `multimesh_set_visible_instances`.
+                vs.multimesh_set_as_bulk_array(batch.multimesh, Float32Array::from_slice(&batch.buffer));
+                vs.multimesh_set_visible_instances(batch.multimesh, len as i64);
+            }
+        }
+    }
+}
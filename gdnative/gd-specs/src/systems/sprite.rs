@@ -0,0 +1,76 @@
+//! This draws the current frame of each `SpriteSheet` from `specs_engine`. Canvas items that are owned by their entity are redrawn with the
+//! region of the frame, while entities with a `CanvasItemShader` (such as hybrid entities) have the frame set as the `frame` shader parameter.
+//! Shaders that are driven this way must declare `uniform int frame;` and select the region of the sheet themselves.
+use gdnative::prelude::*;
+use gdnative::api::{Texture, VisualServer};
+use specs::prelude::*;
+use specs_engine::SpriteSheet;
+
+use crate::components::{CanvasItemShader, CanvasItemTexture, TextureOverride};
+
+/// Updates the entities whose `SpriteSheet` has changed since the last run.
+/// This system must be set up with `Dispatcher::setup` / `System::setup` before it is run.
+#[derive(Default)]
+pub struct VSUpdateSpriteFrames {
+    reader: Option<ReaderId<ComponentEvent>>,
+}
+
+impl <'a> System <'a> for VSUpdateSpriteFrames {
+    type SystemData = (
+        ReadStorage<'a, SpriteSheet>,
+        ReadStorage<'a, crate::components::CanvasItem>,
+        ReadStorage<'a, CanvasItemTexture>,
+        ReadStorage<'a, TextureOverride>,
+        ReadStorage<'a, CanvasItemShader>,
+    );
+    fn setup(&mut self, world: &mut World) {
+        Self::SystemData::setup(world);
+        self.reader = Some(WriteStorage::<SpriteSheet>::fetch(world).register_reader());
+    }
+    fn run(&mut self, data: Self::SystemData) {
+        let (sheets, canvas_items, textures, overrides, shader_materials) = data;
+        let reader = self.reader.as_mut().expect("VSUpdateSpriteFrames::setup has not been called");
+        let mut changed = BitSet::new();
+        for event in sheets.channel().read(reader) {
+            match event {
+                ComponentEvent::Inserted(id) | ComponentEvent::Modified(id) => {
+                    changed.add(*id);
+                },
+                ComponentEvent::Removed(_) => {},
+            }
+        }
+        if changed.is_empty() {
+            return;
+        }
+        let vs = unsafe { VisualServer::godot_singleton() };
+        for (sheet, canvas_item, texture, texture_override, _) in (&sheets, &canvas_items, &textures, overrides.maybe(), &changed).join() {
+            // Canvas items that belong to a node are redrawn by the node, so only owned canvas items can be redrawn here.
+            if !canvas_item.owned {
+                continue;
+            }
+            let texture = texture_override.map_or(&texture.texture, |texture_override| &texture_override.texture);
+            draw_sprite(vs, canvas_item.rid, unsafe { texture.assume_safe() }, Some(sheet));
+        }
+        for (sheet, material, _) in (&sheets, &shader_materials, &changed).join() {
+            let material = unsafe { material.material.assume_safe() };
+            vs.material_set_param(material.get_rid(), "frame", sheet.frame as i64);
+        }
+    }
+}
+
+/// Redraws an owned canvas item with the texture centered on its origin. If there is a `SpriteSheet`, only the region of its frame is drawn.
+pub (crate) fn draw_sprite(vs: &VisualServer, rid: Rid, texture: TRef<Texture>, sheet: Option<&SpriteSheet>) {
+    let size = texture.get_size();
+    let (x, y, width, height) = sheet.map_or((0.0, 0.0, size.x, size.y), |sheet| sheet.region(size.x, size.y));
+    vs.canvas_item_clear(rid);
+    vs.canvas_item_add_texture_rect_region(
+        rid,
+        Rect2::new(Point2::new(-width / 2.0, -height / 2.0), Size2::new(width, height)),
+        texture.get_rid(),
+        Rect2::new(Point2::new(x, y), Size2::new(width, height)),
+        Color::from_rgb(1.0, 1.0, 1.0),
+        false,
+        Rid::new(),
+        true,
+    );
+}
@@ -0,0 +1,106 @@
+//! This keeps the drawn texture of each entity in sync with its `TextureOverride`. The override is drawn while it exists and the original
+//! `CanvasItemTexture` is drawn again once it is removed.
+use gdnative::prelude::*;
+use gdnative::api::VisualServer;
+use specs::prelude::*;
+use specs_engine::{SpriteSheet, Time};
+
+use crate::components::{CanvasItemTexture, GodotNode, TextureOverride};
+use crate::systems::draw_sprite;
+
+/// Counts down timed `TextureOverride`s and removes them once they run out.
+// Note: The `Time` resource MUST be added to the simulation for this system to work.
+pub struct TextureOverrideTimerSystem {}
+
+impl <'a> System <'a> for TextureOverrideTimerSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Time>,
+        WriteStorage<'a, TextureOverride>,
+    );
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, time, mut overrides) = data;
+        let mut expired = Vec::new();
+        // Counting down does not change the drawn texture, so it is not reported to the `VSSyncTextures`.
+        overrides.set_event_emission(false);
+        for (entity, texture_override) in (&entities, &mut overrides).join() {
+            if let Some(remaining) = texture_override.remaining.as_mut() {
+                *remaining -= time.delta;
+                if *remaining <= 0.0 {
+                    expired.push(entity);
+                }
+            }
+        }
+        overrides.set_event_emission(true);
+        for entity in expired {
+            overrides.remove(entity);
+        }
+    }
+}
+
+/// Draws the `TextureOverride` of an entity when it is added or changed, and restores the `CanvasItemTexture` when it is removed.
+/// Entities with a `GodotNode` have the texture set on their `sprite` child, while owned canvas items are redrawn through the `VisualServer`.
+/// As this modifies nodes, it must be added to the dispatcher with `add_thread_local` so that it runs on the main thread.
+/// This system must be set up with `Dispatcher::setup` / `System::setup` before it is run.
+#[derive(Default)]
+pub struct VSSyncTextures {
+    reader: Option<ReaderId<ComponentEvent>>,
+}
+
+impl <'a> System <'a> for VSSyncTextures {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, TextureOverride>,
+        ReadStorage<'a, CanvasItemTexture>,
+        ReadStorage<'a, crate::components::CanvasItem>,
+        ReadStorage<'a, GodotNode>,
+        ReadStorage<'a, SpriteSheet>,
+    );
+    fn setup(&mut self, world: &mut World) {
+        Self::SystemData::setup(world);
+        self.reader = Some(WriteStorage::<TextureOverride>::fetch(world).register_reader());
+    }
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, overrides, textures, canvas_items, nodes, sheets) = data;
+        let reader = self.reader.as_mut().expect("VSSyncTextures::setup has not been called");
+        let mut changed = BitSet::new();
+        let mut removed = BitSet::new();
+        for event in overrides.channel().read(reader) {
+            match event {
+                ComponentEvent::Inserted(id) | ComponentEvent::Modified(id) => {
+                    changed.add(*id);
+                },
+                ComponentEvent::Removed(id) => {
+                    removed.add(*id);
+                },
+            }
+        }
+        if changed.is_empty() && removed.is_empty() {
+            return;
+        }
+        let draw = |entity: Entity, texture: &Ref<Texture>| {
+            if let Some(node) = nodes.get(entity) {
+                let sprite = unsafe { node.node.assume_safe_if_sane() }
+                    .and_then(|node| node.get_node("sprite"))
+                    .and_then(|sprite| unsafe { sprite.assume_safe() }.cast::<Sprite>());
+                match sprite {
+                    Some(sprite) => sprite.set_texture(texture.clone()),
+                    None => log::error!("{:?} does not have a `sprite` child to draw the texture on", entity),
+                }
+            } else if let Some(canvas_item) = canvas_items.get(entity).filter(|canvas_item| canvas_item.owned) {
+                let vs = unsafe { VisualServer::godot_singleton() };
+                draw_sprite(vs, canvas_item.rid, unsafe { texture.assume_safe() }, sheets.get(entity));
+            }
+        };
+        for (entity, texture_override, _) in (&entities, &overrides, &changed).join() {
+            draw(entity, &texture_override.texture);
+        }
+        // Entities that have been deleted are no longer alive, so they are skipped as they do not need to be restored.
+        for (entity, _, _) in (&entities, !&overrides, &removed).join() {
+            match textures.get(entity) {
+                Some(texture) => draw(entity, &texture.texture),
+                None => log::warn!("{:?} does not have a `CanvasItemTexture` to restore", entity),
+            }
+        }
+    }
+}
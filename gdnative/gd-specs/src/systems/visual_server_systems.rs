@@ -1,11 +1,12 @@
 use specs::prelude::*;
 use crate::components::*;
-use specs_engine::{Position, Rotation, Scale, WorldMsgQueue};
+use specs_engine::{Culled, Position, Rotation, Scale, WorldMsgQueue};
 use gdnative::prelude::*;
 use gdnative::api::VisualServer;
 
-use crate::ShaderParams;
+use crate::ShaderUniforms;
 
+/// Entities that have been `Culled` are skipped as they are not visible.
 pub struct VSUpdateTransforms {}
 
 
@@ -15,13 +16,14 @@ impl<'a> System<'a> for VSUpdateTransforms {
         ReadStorage<'a, Position>,
         ReadStorage<'a, Rotation>,
         ReadStorage<'a, Scale>,
+        ReadStorage<'a, Culled>,
     );
     fn run(&mut self, data: Self::SystemData) {
         // Start fresh
-        let (canvas_items, positions, rotations, scales) = data;
+        let (canvas_items, positions, rotations, scales, culled) = data;
         // Synchronize the position of any canvas items that exist
         let vs = unsafe { VisualServer::godot_singleton() };
-        for (ci, position, scale, rotation) in (&canvas_items, &positions, &scales, &rotations).join() {
+        for (ci, position, scale, rotation, _) in (&canvas_items, &positions, &scales, &rotations, !&culled).join() {
             // This is the matrix math to handle rotation and scaling for a 2D object
             let x1 = rotation.radians.cos() * scale.x;
             let x2 = rotation.radians.sin();
@@ -60,12 +62,13 @@ impl<'a> System<'a> for VSUpdateTransformsParallel {
         ReadStorage<'a, Position>,
         ReadStorage<'a, Rotation>,
         ReadStorage<'a, Scale>,
+        ReadStorage<'a, Culled>,
     );
     fn run(&mut self, data: Self::SystemData) {
         // Start fresh
-        let (canvas_items, positions, rotations, scales) = data;
+        let (canvas_items, positions, rotations, scales, culled) = data;
         
-        for (ci, position, scale, rotation) in (&canvas_items, &positions, &scales, &rotations)
+        for (ci, position, scale, rotation, _) in (&canvas_items, &positions, &scales, &rotations, !&culled)
             .join() {
             // .par_join()
             // // Is ther some way to get the visual server for each parallel thread instead of having to grab it for each item
@@ -100,28 +103,60 @@ impl <'a> System <'a> for VSUpdateTransformsThreadLocal {
         }
     }
 }
-pub struct VSUpdateShaderParams {}
+/// Sends the `ShaderUniforms` that have changed to the material of the entity. Changes to entities that have been `Culled` are kept until
+/// they are revealed.
+pub struct VSUpdateShaderUniforms {}
 
-impl <'a> System <'a> for VSUpdateShaderParams {
+impl <'a> System <'a> for VSUpdateShaderUniforms {
     type SystemData = (
         ReadStorage<'a, CanvasItemShader>,
-        ReadStorage<'a, ShaderParams>);
+        WriteStorage<'a, ShaderUniforms>,
+        ReadStorage<'a, Culled>,
+    );
     fn run(&mut self, data: Self::SystemData) {
-        let (shader_materials, shader_params) = data;
-        let vs = unsafe {VisualServer::godot_singleton()};
-        for (material, shader_param) in (&shader_materials, &shader_params).join() {
-            let material = unsafe { material.material.assume_safe() };
-            let rid = material.get_rid();
-            vs.material_set_param(
-                rid,
-                "fg",
-                shader_param.fg,
-            );
-            vs.material_set_param(
-                rid,
-                "bg",
-                shader_param.bg,
-            );
+        let (shader_materials, mut uniforms, culled) = data;
+        let vs = unsafe { VisualServer::godot_singleton() };
+        for (material, uniforms, _) in (&shader_materials, &mut uniforms, !&culled).join() {
+            if !uniforms.has_changes() {
+                continue;
+            }
+            let rid = unsafe { material.material.assume_safe() }.get_rid();
+            uniforms.apply_changes(|name, value| vs.material_set_param(rid, name, value.to_variant()));
         }
     }
-}
\ No newline at end of file
+}
+
+/// Hides the canvas items of entities that have been `Culled` and shows them again once they are revealed.
+/// This system must be set up with `Dispatcher::setup` / `System::setup` before it is run.
+#[derive(Default)]
+pub struct VSUpdateVisibility {
+    reader: Option<ReaderId<ComponentEvent>>,
+}
+
+impl <'a> System <'a> for VSUpdateVisibility {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, Culled>,
+        ReadStorage<'a, crate::components::CanvasItem>,
+    );
+    fn setup(&mut self, world: &mut World) {
+        Self::SystemData::setup(world);
+        self.reader = Some(WriteStorage::<Culled>::fetch(world).register_reader());
+    }
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, culled, canvas_items) = data;
+        let reader = self.reader.as_mut().expect("VSUpdateVisibility::setup has not been called");
+        let vs = unsafe { VisualServer::godot_singleton() };
+        for event in culled.channel().read(reader) {
+            let (id, visible) = match event {
+                ComponentEvent::Inserted(id) => (*id, false),
+                ComponentEvent::Removed(id) => (*id, true),
+                ComponentEvent::Modified(_) => continue,
+            };
+            // Entities that have been deleted no longer have a canvas item.
+            if let Some(canvas_item) = canvas_items.get(entities.entity(id)) {
+                vs.canvas_item_set_visible(canvas_item.rid, visible);
+            }
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! Compares the `SpatialGrid` against a brute force search with the entity counts used by the EndlessSpawner scenes.
+//! Run with `cargo run --release --example spatial_grid_benchmark`
+use specs::prelude::*;
+use specs_engine::*;
+use std::time::Instant;
+
+const ENTITIES: usize = 10_000;
+const QUERIES: usize = 1_000;
+const RADIUS: f32 = 32.0;
+const BOUNDS: f32 = 2048.0;
+
+fn main() {
+    let mut world = World::new();
+    register_components(&mut world);
+    world.insert(SpatialGrid::new(RADIUS));
+    // A simple linear congruential generator keeps each run identical.
+    let mut seed = 42u32;
+    let mut random = move || {
+        seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+        (seed >> 16) as f32 / 65536.0 * BOUNDS
+    };
+    for _ in 0..ENTITIES {
+        world.create_entity().with(Position { x: random(), y: random() }).build();
+    }
+    let queries: Vec<(f32, f32)> = (0..QUERIES).map(|_| (random(), random())).collect();
+
+    let mut update = UpdateSpatialGridSystem {};
+    let start = Instant::now();
+    update.run_now(&world);
+    println!("grid update ({} entities): {:?}", ENTITIES, start.elapsed());
+
+    let start = Instant::now();
+    let mut grid_found = 0;
+    for (x, y) in queries.iter() {
+        grid_found += EntitiesInRadius::query(&world, (*x, *y, RADIUS)).len();
+    }
+    println!("grid queries ({}): {:?}", QUERIES, start.elapsed());
+
+    let start = Instant::now();
+    let mut brute_found = 0;
+    let positions = world.read_storage::<Position>();
+    for (x, y) in queries.iter() {
+        for position in (&positions).join() {
+            let dx = position.x - x;
+            let dy = position.y - y;
+            if dx * dx + dy * dy <= RADIUS * RADIUS {
+                brute_found += 1;
+            }
+        }
+    }
+    println!("brute force queries ({}): {:?}", QUERIES, start.elapsed());
+    assert_eq!(grid_found, brute_found);
+}
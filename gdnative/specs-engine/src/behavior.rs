@@ -0,0 +1,347 @@
+//! This contains the runtime for behaviour trees. Trees are defined as data with `BehaviorNodeDef`, which can be loaded from RON or JSON when the
+//! `data` feature is enabled, and are then built into a `BehaviorTemplate` using the leaves in the `BehaviorRegistry`.
+//! Each entity with a `BehaviorTree` shares the template but has its own blackboard and running state.
+use std::collections::HashMap;
+use std::sync::Arc;
+use specs::prelude::*;
+use crate::commands::DeferredCommand;
+use crate::components::BehaviorTree;
+use crate::resources::WorldMsgQueue;
+use crate::util::WorldCommand;
+
+/// The result of ticking a node.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Status {
+    Success,
+    Failure,
+    Running,
+}
+
+/// The definition of a behaviour tree node.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "data", derive(serde::Serialize, serde::Deserialize))]
+pub enum BehaviorNodeDef {
+    /// Runs the children in order until one of them succeeds.
+    Selector(Vec<BehaviorNodeDef>),
+    /// Runs the children in order until one of them fails.
+    Sequence(Vec<BehaviorNodeDef>),
+    /// Runs all of the children each tick. Succeeds once `success_threshold` children have succeeded and fails once that is no longer possible.
+    Parallel { success_threshold: usize, children: Vec<BehaviorNodeDef> },
+    /// Swaps the success and failure of the child.
+    Inverter(Box<BehaviorNodeDef>),
+    /// Succeeds even if the child fails.
+    Succeeder(Box<BehaviorNodeDef>),
+    /// Runs the child again each time that it succeeds, either `times` times or forever if it is `None`. Fails as soon as the child fails.
+    Repeat { times: Option<u32>, child: Box<BehaviorNodeDef> },
+    /// A leaf that is looked up by name in the `BehaviorRegistry`.
+    Leaf(String),
+}
+
+#[cfg(feature = "data")]
+impl BehaviorNodeDef {
+    pub fn from_ron(text: &str) -> Result<Self, String> {
+        ron::de::from_str(text).map_err(|err| err.to_string())
+    }
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        serde_json::from_str(text).map_err(|err| err.to_string())
+    }
+}
+
+/// A value stored on a blackboard.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BlackboardValue {
+    Bool(bool),
+    Int(i64),
+    Float(f32),
+    Point(f32, f32),
+    Entity(Entity),
+    Text(String),
+}
+
+/// Per entity memory that the leaves of a tree use to share information, eg. a target found by one leaf and then moved to by another.
+#[derive(Debug, Clone, Default)]
+pub struct Blackboard(HashMap<String, BlackboardValue>);
+
+impl Blackboard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn get(&self, key: &str) -> Option<&BlackboardValue> {
+        self.0.get(key)
+    }
+    pub fn set(&mut self, key: &str, value: BlackboardValue) {
+        self.0.insert(key.to_owned(), value);
+    }
+    pub fn remove(&mut self, key: &str) -> Option<BlackboardValue> {
+        self.0.remove(key)
+    }
+    pub fn get_entity(&self, key: &str) -> Option<Entity> {
+        match self.0.get(key) {
+            Some(BlackboardValue::Entity(entity)) => Some(*entity),
+            _ => None,
+        }
+    }
+    pub fn get_point(&self, key: &str) -> Option<(f32, f32)> {
+        match self.0.get(key) {
+            Some(BlackboardValue::Point(x, y)) => Some((*x, *y)),
+            _ => None,
+        }
+    }
+    pub fn get_float(&self, key: &str) -> Option<f32> {
+        match self.0.get(key) {
+            Some(BlackboardValue::Float(value)) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+/// Everything that a leaf can access while it is ticked.
+/// The world is shared with every other tree that is being ticked in parallel, so it can only be read. Any changes must be deferred.
+/// Note: The `BehaviorTree` storage is locked while the trees are ticked and MUST NOT be fetched from the world.
+pub struct LeafContext<'a> {
+    pub entity: Entity,
+    pub world: &'a World,
+    pub blackboard: &'a mut Blackboard,
+    pub delta: f32,
+    commands: &'a WorldMsgQueue<DeferredCommand>,
+}
+
+impl <'a> LeafContext<'a> {
+    /// Queues the command to run once all of the trees have been ticked.
+    pub fn defer(&self, command: DeferredCommand) {
+        self.commands.push(command);
+    }
+}
+
+pub type LeafFn = Arc<dyn Fn(&mut LeafContext) -> Status + Send + Sync>;
+
+/// The leaves that can be referenced by name from a `BehaviorNodeDef`.
+#[derive(Default)]
+pub struct BehaviorRegistry {
+    leaves: HashMap<String, LeafFn>,
+}
+
+impl BehaviorRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn register_leaf<F>(&mut self, name: &str, leaf: F)
+        where F: Fn(&mut LeafContext) -> Status + Send + Sync + 'static
+    {
+        self.leaves.insert(name.to_owned(), Arc::new(leaf));
+    }
+    /// Registers a leaf that defers the command for the entity and then succeeds.
+    pub fn register_command<C>(&mut self, name: &str)
+        where C: WorldCommand<Args = Entity> + 'static
+    {
+        self.register_leaf(name, |context| {
+            context.defer(DeferredCommand::new::<C>(context.entity));
+            Status::Success
+        });
+    }
+    /// Resolves the leaves of the definition. Fails if a leaf has not been registered or a composite has no children.
+    pub fn build(&self, definition: &BehaviorNodeDef) -> Result<Arc<BehaviorTemplate>, String> {
+        let mut nodes = Vec::new();
+        self.build_node(definition, &mut nodes)?;
+        Ok(Arc::new(BehaviorTemplate { nodes }))
+    }
+    fn build_node(&self, definition: &BehaviorNodeDef, nodes: &mut Vec<Node>) -> Result<usize, String> {
+        // The index is reserved first so that the root is always the first node.
+        let index = nodes.len();
+        nodes.push(Node::Succeeder(usize::MAX));
+        let node = match definition {
+            BehaviorNodeDef::Selector(children) => Node::Selector(self.build_children(children, nodes)?),
+            BehaviorNodeDef::Sequence(children) => Node::Sequence(self.build_children(children, nodes)?),
+            BehaviorNodeDef::Parallel { success_threshold, children } => {
+                if *success_threshold > children.len() {
+                    return Err(format!("parallel node needs {} successes but only has {} children", success_threshold, children.len()));
+                }
+                Node::Parallel(*success_threshold, self.build_children(children, nodes)?)
+            },
+            BehaviorNodeDef::Inverter(child) => Node::Inverter(self.build_node(child, nodes)?),
+            BehaviorNodeDef::Succeeder(child) => Node::Succeeder(self.build_node(child, nodes)?),
+            BehaviorNodeDef::Repeat { times, child } => Node::Repeat(*times, self.build_node(child, nodes)?),
+            BehaviorNodeDef::Leaf(name) => match self.leaves.get(name) {
+                Some(leaf) => Node::Leaf(leaf.clone()),
+                None => return Err(format!("leaf {} has not been registered", name)),
+            },
+        };
+        nodes[index] = node;
+        Ok(index)
+    }
+    fn build_children(&self, children: &[BehaviorNodeDef], nodes: &mut Vec<Node>) -> Result<Vec<usize>, String> {
+        if children.is_empty() {
+            return Err("composite nodes must have at least one child".to_owned());
+        }
+        children.iter().map(|child| self.build_node(child, nodes)).collect()
+    }
+}
+
+pub (crate) enum Node {
+    Selector(Vec<usize>),
+    Sequence(Vec<usize>),
+    Parallel(usize, Vec<usize>),
+    Inverter(usize),
+    Succeeder(usize),
+    Repeat(Option<u32>, usize),
+    Leaf(LeafFn),
+}
+
+/// A tree whose leaves have been resolved, this is shared between all of the entities that use it.
+pub struct BehaviorTemplate {
+    pub (crate) nodes: Vec<Node>,
+}
+
+impl BehaviorTemplate {
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+impl BehaviorTree {
+    /// Ticks the tree from the root and returns the status of the root.
+    pub (crate) fn tick(&mut self, entity: Entity, world: &World, delta: f32, commands: &WorldMsgQueue<DeferredCommand>) -> Status {
+        let template = self.template.clone();
+        let mut context = LeafContext { entity, world, blackboard: &mut self.blackboard, delta, commands };
+        let status = tick_node(&template, 0, &mut self.counters, &mut self.finished, &mut context);
+        self.status = Some(status);
+        status
+    }
+}
+
+fn reset(template: &BehaviorTemplate, index: usize, counters: &mut [u32], finished: &mut [Option<Status>]) {
+    counters[index] = 0;
+    finished[index] = None;
+    match &template.nodes[index] {
+        Node::Selector(children) | Node::Sequence(children) | Node::Parallel(_, children) => {
+            for child in children.iter() {
+                reset(template, *child, counters, finished);
+            }
+        },
+        Node::Inverter(child) | Node::Succeeder(child) | Node::Repeat(_, child) => reset(template, *child, counters, finished),
+        Node::Leaf(_) => {},
+    }
+}
+
+// `counters` holds the running child of selectors and sequences and the completed repetitions of repeats.
+// `finished` holds the status of the children of parallel nodes that have already completed.
+fn tick_node(template: &BehaviorTemplate, index: usize, counters: &mut [u32], finished: &mut [Option<Status>], context: &mut LeafContext) -> Status {
+    match &template.nodes[index] {
+        Node::Selector(children) | Node::Sequence(children) => {
+            // A selector stops at the first success and a sequence stops at the first failure.
+            let stop_at = if let Node::Selector(_) = template.nodes[index] { Status::Success } else { Status::Failure };
+            while (counters[index] as usize) < children.len() {
+                let status = tick_node(template, children[counters[index] as usize], counters, finished, context);
+                if status == Status::Running {
+                    return Status::Running;
+                }
+                if status == stop_at {
+                    counters[index] = 0;
+                    return status;
+                }
+                counters[index] += 1;
+            }
+            counters[index] = 0;
+            if stop_at == Status::Success { Status::Failure } else { Status::Success }
+        },
+        Node::Parallel(success_threshold, children) => {
+            let (mut successes, mut failures) = (0, 0);
+            for child in children.iter() {
+                let status = match finished[*child] {
+                    Some(status) => status,
+                    None => tick_node(template, *child, counters, finished, context),
+                };
+                match status {
+                    Status::Success => successes += 1,
+                    Status::Failure => failures += 1,
+                    Status::Running => continue,
+                }
+                finished[*child] = Some(status);
+            }
+            let status = if successes >= *success_threshold {
+                Status::Success
+            } else if failures > children.len() - success_threshold {
+                Status::Failure
+            } else {
+                return Status::Running;
+            };
+            // Any children that are still running are interrupted.
+            reset(template, index, counters, finished);
+            status
+        },
+        Node::Inverter(child) => match tick_node(template, *child, counters, finished, context) {
+            Status::Success => Status::Failure,
+            Status::Failure => Status::Success,
+            Status::Running => Status::Running,
+        },
+        Node::Succeeder(child) => match tick_node(template, *child, counters, finished, context) {
+            Status::Running => Status::Running,
+            _ => Status::Success,
+        },
+        Node::Repeat(times, child) => match tick_node(template, *child, counters, finished, context) {
+            Status::Running => Status::Running,
+            Status::Failure => {
+                counters[index] = 0;
+                Status::Failure
+            },
+            Status::Success => {
+                counters[index] += 1;
+                if times.is_some_and(|times| counters[index] >= times) {
+                    counters[index] = 0;
+                    Status::Success
+                } else {
+                    Status::Running
+                }
+            },
+        },
+        Node::Leaf(leaf) => leaf(context),
+    }
+}
+
+#[cfg(all(test, feature = "data"))]
+mod test {
+    use super::*;
+
+    fn guard_tree() -> BehaviorNodeDef {
+        BehaviorNodeDef::Selector(vec![
+            BehaviorNodeDef::Sequence(vec![
+                BehaviorNodeDef::Leaf("can_see_player".to_owned()),
+                BehaviorNodeDef::Inverter(Box::new(BehaviorNodeDef::Leaf("is_fleeing".to_owned()))),
+                BehaviorNodeDef::Leaf("chase".to_owned()),
+            ]),
+            BehaviorNodeDef::Repeat {
+                times: Some(2),
+                child: Box::new(BehaviorNodeDef::Succeeder(Box::new(BehaviorNodeDef::Leaf("patrol".to_owned())))),
+            },
+        ])
+    }
+
+    #[test]
+    pub fn test_tree_from_ron() {
+        let text = r#"
+            Selector([
+                Sequence([Leaf("can_see_player"), Inverter(Leaf("is_fleeing")), Leaf("chase")]),
+                Repeat(times: Some(2), child: Succeeder(Leaf("patrol"))),
+            ])
+        "#;
+        assert_eq!(BehaviorNodeDef::from_ron(text), Ok(guard_tree()));
+        let written = ron::ser::to_string(&guard_tree()).expect("the tree should serialize");
+        assert_eq!(BehaviorNodeDef::from_ron(&written), Ok(guard_tree()));
+        assert!(BehaviorNodeDef::from_ron("Selector(").is_err());
+    }
+
+    #[test]
+    pub fn test_tree_from_json() {
+        let text = r#"{"Selector": [
+            {"Sequence": [{"Leaf": "can_see_player"}, {"Inverter": {"Leaf": "is_fleeing"}}, {"Leaf": "chase"}]},
+            {"Repeat": {"times": 2, "child": {"Succeeder": {"Leaf": "patrol"}}}}
+        ]}"#;
+        assert_eq!(BehaviorNodeDef::from_json(text), Ok(guard_tree()));
+        let written = serde_json::to_string(&guard_tree()).expect("the tree should serialize");
+        assert_eq!(BehaviorNodeDef::from_json(&written), Ok(guard_tree()));
+        assert!(BehaviorNodeDef::from_json("{\"Selector\": ").is_err());
+    }
+}
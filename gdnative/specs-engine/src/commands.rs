@@ -0,0 +1,58 @@
+//! This contains `WorldCommand`s that are generally useful and the queue used to defer them from inside of systems.
+use specs::prelude::*;
+use crate::components::DespawnRequest;
+use crate::resources::WorldMsgQueue;
+use crate::util::WorldCommand;
+
+/// Systems cannot get exclusive access to the `World`, so any `WorldCommand` that they need to run is boxed and
+/// pushed onto a `WorldMsgQueue<DeferredCommand>`. The queue is then emptied by `ApplyDeferredCommands` once the dispatcher has finished.
+pub struct DeferredCommand(Box<dyn FnOnce(&mut World) + Send + Sync>);
+
+impl DeferredCommand {
+    /// Captures the arguments for `C` so that it can be executed later.
+    pub fn new<C>(args: C::Args) -> Self
+        where C: WorldCommand + 'static,
+        C::Args: Send + Sync + 'static
+    {
+        Self(Box::new(move |world| { C::execute(world, args); }))
+    }
+    pub fn execute(self, world: &mut World) {
+        (self.0)(world)
+    }
+}
+
+/// Runs every command in the `WorldMsgQueue<DeferredCommand>` and returns the number of commands that were applied.
+/// This should be called after the dispatcher has run, but before `World::maintain` so that any deletions are committed the same frame.
+pub struct ApplyDeferredCommands {}
+
+impl WorldCommand for ApplyDeferredCommands {
+    type Args = ();
+    type Output = usize;
+    fn execute(world: &mut World, _: Self::Args) -> Self::Output {
+        let mut applied = 0;
+        // The queue has to be fetched for each command as the command itself requires mutable access to the world.
+        loop {
+            let command = world.try_fetch::<WorldMsgQueue<DeferredCommand>>().and_then(|queue| queue.pop());
+            if let Some(command) = command {
+                command.execute(world);
+                applied += 1;
+            } else {
+                break;
+            }
+        }
+        applied
+    }
+}
+
+/// Requests that the entity is removed by the despawn pipeline on the next run.
+pub struct DespawnEntity {}
+
+impl WorldCommand for DespawnEntity {
+    type Args = Entity;
+    type Output = ();
+    fn execute(world: &mut World, entity: Self::Args) -> Self::Output {
+        if let Err(err) = world.write_storage::<DespawnRequest>().insert(entity, DespawnRequest) {
+            log::warn!("could not despawn {:?}: {}", entity, err);
+        }
+    }
+}
@@ -3,11 +3,43 @@ use gdnative::prelude::*;
 
 use specs::prelude::*;
 use specs_derive::Component;
+use crate::gameplay::{Damage, Health, Stats};
 
 #[cfg(feature = "godot")]
 mod godot_ext;
 #[cfg(feature = "godot")]
 pub use godot_ext::*;
+
+mod animation;
+mod behavior;
+mod boundary;
+mod camera;
+mod collision;
+mod culling;
+mod draw_order;
+mod dynamics;
+mod naming;
+mod navigation;
+mod state_machine;
+mod steering;
+mod timer;
+mod tween;
+mod utility;
+pub use animation::*;
+pub use behavior::*;
+pub use boundary::*;
+pub use camera::*;
+pub use collision::*;
+pub use culling::*;
+pub use draw_order::*;
+pub use dynamics::*;
+pub use naming::*;
+pub use navigation::*;
+pub use state_machine::*;
+pub use steering::*;
+pub use timer::*;
+pub use tween::*;
+pub use utility::*;
 /// Defines the position of an entity in 2D space
 
 #[derive(Debug, Component)]
@@ -54,6 +86,12 @@ pub struct SetVelocityIntent {
     pub y: f32,
 }
 
+/// This identifies the entities that are controlled by the player's input
+#[derive(Debug, Default, Component)]
+#[storage(NullStorage)]
+#[cfg_attr(feature = "godot", derive(ToVariant))]
+pub struct Player;
+
 /// This identifies which entities must respect the bounding box when moving
 #[derive(Debug, Default, Component)]
 #[storage(NullStorage)]
@@ -73,6 +111,21 @@ pub struct TreeRelationship {
     pub children: Vec<Entity>,
 }
 
+/// The number of seconds that an entity has left before it requests to be despawned.
+#[derive(Debug, Component)]
+#[cfg_attr(feature = "godot", derive(ToVariant))]
+pub struct Lifetime {
+    pub remaining: f32,
+}
+
+/// Marks an entity to be removed by the despawn pipeline. Any children in the `TreeRelationship` will also be despawned.
+/// Entities should not be deleted directly if they own resources outside of the ECS (such as rids or physics bodies),
+/// adding this component gives each system in the pipeline the chance to clean them up first.
+#[derive(Debug, Default, Component)]
+#[storage(NullStorage)]
+#[cfg_attr(feature = "godot", derive(ToVariant))]
+pub struct DespawnRequest;
+
 /// This represents a "tree-like" relationship between entities. The current entity may index a parent and a list of children
 /// This is used to mimic the scene-tree relationship that allows for objects to rotate in place.
 #[derive(Debug, Default, Component)]
@@ -88,7 +141,49 @@ pub fn register_components(world: &mut World) {
     world.register::<AngularVelocity>();
     world.register::<SetVelocityIntent>();
     world.register::<StayInsideBounds>();
+    world.register::<Player>();
     world.register::<Counter>();
     world.register::<TreeRelationship>();
     world.register::<StringContainer>();
+    world.register::<Lifetime>();
+    world.register::<DespawnRequest>();
+    world.register::<Timer>();
+    world.register::<Cooldown>();
+    world.register::<TransformTween>();
+    world.register::<Name>();
+    world.register::<Tags>();
+    world.register::<Steering>();
+    world.register::<Seek>();
+    world.register::<Flee>();
+    world.register::<Arrive>();
+    world.register::<Wander>();
+    world.register::<Separation>();
+    world.register::<Alignment>();
+    world.register::<Cohesion>();
+    world.register::<Acceleration>();
+    world.register::<Mass>();
+    world.register::<Drag>();
+    world.register::<MaxSpeed>();
+    world.register::<BoundaryBehavior>();
+    world.register::<SpriteSheet>();
+    world.register::<SpriteAnimation>();
+    world.register::<Camera>();
+    world.register::<CameraShake>();
+    world.register::<CullBounds>();
+    world.register::<Culled>();
+    world.register::<ZIndex>();
+    world.register::<YSort>();
+    world.register::<DrawOrder>();
+    world.register::<CircleCollider>();
+    world.register::<AabbCollider>();
+    world.register::<CollisionLayers>();
+    world.register::<PathFollow>();
+    world.register::<FollowFlowField>();
+    world.register::<CurrentState>();
+    world.register::<BehaviorTree>();
+    world.register::<UtilityAi>();
+    world.register::<Intent>();
+    world.register::<Health>();
+    world.register::<Damage>();
+    world.register::<Stats>();
 }
\ No newline at end of file
@@ -0,0 +1,80 @@
+use specs::prelude::*;
+use specs_derive::Component;
+use std::collections::HashMap;
+
+/// A texture that is divided into a grid of `hframes` by `vframes` frames, numbered from left to right and then top to bottom.
+/// Changes are tracked so that the renderer only needs to update the entities whose frame has changed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpriteSheet {
+    pub hframes: u32,
+    pub vframes: u32,
+    pub frame: u32,
+}
+
+impl Component for SpriteSheet {
+    type Storage = FlaggedStorage<Self, DenseVecStorage<Self>>;
+}
+
+impl SpriteSheet {
+    pub fn new(hframes: u32, vframes: u32) -> Self {
+        assert!(hframes > 0 && vframes > 0);
+        Self { hframes, vframes, frame: 0 }
+    }
+    pub fn frame_count(&self) -> u32 {
+        self.hframes * self.vframes
+    }
+    /// Returns the region of the current frame in a texture of the given size as `(x, y, width, height)`.
+    pub fn region(&self, texture_width: f32, texture_height: f32) -> (f32, f32, f32, f32) {
+        let (width, height) = (texture_width / self.hframes as f32, texture_height / self.vframes as f32);
+        let frame = self.frame % self.frame_count();
+        ((frame % self.hframes) as f32 * width, (frame / self.hframes) as f32 * height, width, height)
+    }
+}
+
+/// Plays named clips of frames from the `SpriteSheet` at `fps` frames per second. When a clip that does not loop reaches its last frame,
+/// it stays there and an `AnimationFinished` event is sent.
+#[derive(Debug, Clone, Component)]
+pub struct SpriteAnimation {
+    pub clips: HashMap<&'static str, Vec<u32>>,
+    pub current: &'static str,
+    pub fps: f32,
+    pub looping: bool,
+    pub (crate) index: usize,
+    pub (crate) elapsed: f32,
+    pub (crate) finished: bool,
+}
+
+impl SpriteAnimation {
+    pub fn new(fps: f32, looping: bool) -> Self {
+        Self { clips: HashMap::new(), current: "", fps, looping, index: 0, elapsed: 0.0, finished: false }
+    }
+    /// Adds a clip, the first clip that is added will be played.
+    pub fn with_clip(mut self, name: &'static str, frames: Vec<u32>) -> Self {
+        if self.clips.is_empty() {
+            self.current = name;
+        }
+        self.clips.insert(name, frames);
+        self
+    }
+    /// Plays the clip from the start, unless it is already playing.
+    pub fn play(&mut self, name: &'static str) {
+        if !self.clips.contains_key(name) {
+            log::warn!("sprite animation does not have a clip named {}", name);
+        } else if self.current != name {
+            self.current = name;
+            self.restart();
+        }
+    }
+    pub fn restart(&mut self) {
+        self.index = 0;
+        self.elapsed = 0.0;
+        self.finished = false;
+    }
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+    /// The frame of the sprite sheet for the current point in the clip.
+    pub fn frame(&self) -> Option<u32> {
+        self.clips.get(self.current).and_then(|frames| frames.get(self.index)).copied()
+    }
+}
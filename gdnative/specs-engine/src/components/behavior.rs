@@ -0,0 +1,29 @@
+use std::sync::Arc;
+use specs::prelude::*;
+use specs_derive::Component;
+use crate::behavior::{BehaviorTemplate, Blackboard, Status};
+
+/// Runs a behaviour tree for the entity, this is ticked by the `BehaviorTreeSystem`.
+#[derive(Component)]
+pub struct BehaviorTree {
+    pub blackboard: Blackboard,
+    pub (crate) template: Arc<BehaviorTemplate>,
+    pub (crate) counters: Vec<u32>,
+    pub (crate) finished: Vec<Option<Status>>,
+    pub (crate) status: Option<Status>,
+}
+
+impl BehaviorTree {
+    pub fn new(template: Arc<BehaviorTemplate>) -> Self {
+        let len = template.len();
+        Self { blackboard: Blackboard::new(), template, counters: vec![0; len], finished: vec![None; len], status: None }
+    }
+    pub fn with_blackboard(mut self, blackboard: Blackboard) -> Self {
+        self.blackboard = blackboard;
+        self
+    }
+    /// The status of the root after the last tick, `None` if the tree has not been ticked.
+    pub fn status(&self) -> Option<Status> {
+        self.status
+    }
+}
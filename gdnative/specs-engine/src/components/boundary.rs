@@ -0,0 +1,37 @@
+use specs::prelude::*;
+use specs_derive::Component;
+
+/// What happens to an entity when it leaves its bounding region.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BoundaryMode {
+    /// Reflects the entity back inside and reverses its velocity. The velocity is scaled by `restitution`, 1.0 loses no speed.
+    Bounce { restitution: f32 },
+    /// Moves the entity to the opposite edge, as in asteroids.
+    Wrap,
+    /// Stops the entity at the edge.
+    Clamp,
+    /// Requests a despawn as soon as the entity is outside, this is useful for bullets.
+    Despawn,
+    /// Leaves the entity where it is and only emits a `BoundaryCrossed` event.
+    Event,
+}
+
+/// Determines how the `BoundaryBehaviorSystem` keeps the entity inside of a bounding region.
+/// If `region` is `None` the `BoundingBox` resource is used, otherwise the region is looked up in the `BoundingRegions` resource.
+#[derive(Debug, Component)]
+pub struct BoundaryBehavior {
+    pub mode: BoundaryMode,
+    pub region: Option<&'static str>,
+    // Events are only emitted when the entity moves from inside to outside, not for every frame that it is outside.
+    pub (crate) outside: bool,
+}
+
+impl BoundaryBehavior {
+    pub fn new(mode: BoundaryMode) -> Self {
+        Self { mode, region: None, outside: false }
+    }
+    pub fn in_region(mut self, region: &'static str) -> Self {
+        self.region = Some(region);
+        self
+    }
+}
@@ -0,0 +1,113 @@
+use specs::prelude::*;
+use specs_derive::Component;
+use crate::resources::BoundingBox;
+
+/// A camera that looks at a point in the world, similar to Godot's `Camera2D`. The `CameraSystem` moves it and writes the view of the
+/// `current` camera into the `CameraView` resource, which the renderer then applies to the canvas.
+/// `zoom` is a magnification, so values above 1.0 zoom in (this is the inverse of `Camera2D.zoom`).
+#[derive(Debug, Component)]
+#[storage(HashMapStorage)]
+pub struct Camera {
+    pub current: bool,
+    /// The entity whose `Position` is followed.
+    pub target: Option<Entity>,
+    pub zoom: f32,
+    /// How quickly the camera catches up with the target, as the fraction of the distance covered in 1/60 of a second. `None` snaps to the target.
+    pub smoothing: Option<f32>,
+    /// The half extents of the area around the camera that the target can move in without the camera following.
+    pub dead_zone: (f32, f32),
+    /// The area that the view must stay inside of.
+    pub limits: Option<BoundingBox>,
+    pub (crate) x: f32,
+    pub (crate) y: f32,
+}
+
+impl Camera {
+    pub fn new(x: f32, y: f32) -> Self {
+        Self { current: true, target: None, zoom: 1.0, smoothing: None, dead_zone: (0.0, 0.0), limits: None, x, y }
+    }
+    pub fn following(mut self, target: Entity) -> Self {
+        self.target = Some(target);
+        self
+    }
+    pub fn with_zoom(mut self, zoom: f32) -> Self {
+        self.zoom = zoom;
+        self
+    }
+    pub fn with_smoothing(mut self, smoothing: f32) -> Self {
+        self.smoothing = Some(smoothing);
+        self
+    }
+    pub fn with_dead_zone(mut self, half_width: f32, half_height: f32) -> Self {
+        self.dead_zone = (half_width, half_height);
+        self
+    }
+    pub fn with_limits(mut self, limits: BoundingBox) -> Self {
+        self.limits = Some(limits);
+        self
+    }
+    /// The point in the world at the center of the view, before any shake is applied.
+    pub fn center(&self) -> (f32, f32) {
+        (self.x, self.y)
+    }
+}
+
+/// Trauma based screen shake for a `Camera`. The shake is proportional to the square of the trauma, so small amounts of trauma are
+/// barely noticeable while large amounts shake violently. Trauma is added by gameplay (such as explosions) and decays over time.
+/// The shake uses smooth noise seeded by `seed`, so the same trauma always produces the same shake.
+#[derive(Debug, Component)]
+#[storage(HashMapStorage)]
+pub struct CameraShake {
+    /// The trauma lost each second.
+    pub decay: f32,
+    /// The offset in pixels at full trauma.
+    pub max_offset: f32,
+    /// The rotation in radians at full trauma.
+    pub max_rotation: f32,
+    /// How many times per second the noise changes direction.
+    pub frequency: f32,
+    pub seed: u32,
+    pub (crate) trauma: f32,
+    pub (crate) time: f32,
+}
+
+impl CameraShake {
+    pub fn new(decay: f32, max_offset: f32, max_rotation: f32) -> Self {
+        Self { decay, max_offset, max_rotation, frequency: 15.0, seed: 1, trauma: 0.0, time: 0.0 }
+    }
+    /// Adds trauma between 0.0 and 1.0, the total trauma cannot exceed 1.0.
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+    }
+    pub fn trauma(&self) -> f32 {
+        self.trauma
+    }
+    /// Returns the offset and rotation of the shake at the current time.
+    pub fn shake(&self) -> (f32, f32, f32) {
+        let shake = self.trauma * self.trauma;
+        let t = self.time * self.frequency;
+        (
+            self.max_offset * shake * noise(self.seed, t),
+            self.max_offset * shake * noise(self.seed.wrapping_add(1), t),
+            self.max_rotation * shake * noise(self.seed.wrapping_add(2), t),
+        )
+    }
+}
+
+/// Smooth value noise between -1.0 and 1.0.
+fn noise(seed: u32, t: f32) -> f32 {
+    let i = t.floor();
+    let f = t - i;
+    let f = f * f * (3.0 - 2.0 * f);
+    let a = hash(seed, i as i32);
+    let b = hash(seed, i as i32 + 1);
+    a + (b - a) * f
+}
+
+fn hash(seed: u32, i: i32) -> f32 {
+    let mut h = seed.wrapping_mul(0x9E37_79B9) ^ (i as u32).wrapping_mul(0x85EB_CA6B);
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x7FEB_352D);
+    h ^= h >> 15;
+    (h as f32 / u32::MAX as f32) * 2.0 - 1.0
+}
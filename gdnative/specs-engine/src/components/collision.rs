@@ -0,0 +1,38 @@
+use specs::prelude::*;
+use specs_derive::Component;
+
+/// A circle centered on the `Position` of the entity, used for overlap checks by the `OverlapSystem`.
+#[derive(Debug, Clone, Copy, Component)]
+pub struct CircleCollider {
+    pub radius: f32,
+}
+
+/// An axis aligned box centered on the `Position` of the entity, used for overlap checks by the `OverlapSystem`.
+#[derive(Debug, Clone, Copy, Component)]
+pub struct AabbCollider {
+    pub half_width: f32,
+    pub half_height: f32,
+}
+
+/// `layer` is the set of layers that the collider is on and `mask` is the set of layers that it detects.
+/// A pair overlaps if either collider detects the other. Colliders without `CollisionLayers` are on the first layer and detect every layer.
+#[derive(Debug, Clone, Copy, Component)]
+pub struct CollisionLayers {
+    pub layer: u32,
+    pub mask: u32,
+}
+
+impl Default for CollisionLayers {
+    fn default() -> Self {
+        Self { layer: 1, mask: u32::MAX }
+    }
+}
+
+impl CollisionLayers {
+    pub fn new(layer: u32, mask: u32) -> Self {
+        Self { layer, mask }
+    }
+    pub fn detects(&self, other: &CollisionLayers) -> bool {
+        self.mask & other.layer != 0
+    }
+}
@@ -0,0 +1,18 @@
+use specs::prelude::*;
+use specs_derive::Component;
+
+/// The half extents of the area around the `Position` of an entity that must be inside of the `ViewRect` for it to be drawn.
+#[derive(Debug, Component)]
+pub struct CullBounds {
+    pub half_width: f32,
+    pub half_height: f32,
+}
+
+/// Added by the `CullingSystem` to entities that are outside of the `ViewRect`. Systems that update the renderer should skip these entities.
+/// Changes are tracked so that the renderer only needs to change the visibility of entities that have been culled or revealed.
+#[derive(Debug, Default)]
+pub struct Culled;
+
+impl Component for Culled {
+    type Storage = FlaggedStorage<Self, NullStorage<Self>>;
+}
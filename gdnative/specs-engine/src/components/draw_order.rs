@@ -0,0 +1,38 @@
+use specs::prelude::*;
+use specs_derive::Component;
+#[cfg(feature = "godot")]
+use gdnative::prelude::*;
+
+/// The z index to draw the entity at, entities with a higher z index are drawn on top.
+#[derive(Debug, Default, Component)]
+#[cfg_attr(feature = "godot", derive(ToVariant))]
+pub struct ZIndex(pub i32);
+
+/// Entities with this marker that share a z index are drawn in order of their `Position.y`, so that entities lower on the screen are drawn
+/// in front. This gives top-down games correct depth sorting.
+#[derive(Debug, Default, Component)]
+#[storage(NullStorage)]
+#[cfg_attr(feature = "godot", derive(ToVariant))]
+pub struct YSort;
+
+/// The draw order calculated by the `DrawOrderSystem` from the `ZIndex` and `YSort` of an entity.
+/// This is only modified when the draw order changes so that the renderer can use its change events to skip every other entity.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct DrawOrder {
+    pub (crate) z_index: i32,
+    pub (crate) draw_index: i32,
+}
+
+impl Component for DrawOrder {
+    type Storage = FlaggedStorage<Self, DenseVecStorage<Self>>;
+}
+
+impl DrawOrder {
+    pub fn z_index(&self) -> i32 {
+        self.z_index
+    }
+    /// The position of the entity amongst the other entities that are drawn in the same canvas item.
+    pub fn draw_index(&self) -> i32 {
+        self.draw_index
+    }
+}
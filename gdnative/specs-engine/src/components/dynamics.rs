@@ -0,0 +1,29 @@
+use specs::prelude::*;
+use specs_derive::Component;
+#[cfg(feature = "godot")]
+use gdnative::prelude::*;
+
+/// The constant acceleration (such as gravity) of an entity in 2D space.
+/// Entities with an `Acceleration` are moved by the `IntegrateDynamicsSystem` instead of the `UpdatePositionSystem`.
+#[derive(Debug, Default, Component)]
+#[cfg_attr(feature = "godot", derive(ToVariant))]
+pub struct Acceleration {
+    pub x: f32,
+    pub y: f32,
+}
+
+/// The mass of an entity, this scales the effect of any `Impulse` that is applied to it. Entities without a `Mass` are treated as having a mass of 1.
+#[derive(Debug, Component)]
+#[cfg_attr(feature = "godot", derive(ToVariant))]
+pub struct Mass(pub f32);
+
+/// The fraction of the velocity that is lost each second, between 0.0 (no drag) and 1.0 (stops immediately).
+/// This is applied exponentially so that the result is the same regardless of the frame rate.
+#[derive(Debug, Component)]
+#[cfg_attr(feature = "godot", derive(ToVariant))]
+pub struct Drag(pub f32);
+
+/// Limits the speed of an entity after the `Acceleration`, `Drag` and any impulses have been applied.
+#[derive(Debug, Component)]
+#[cfg_attr(feature = "godot", derive(ToVariant))]
+pub struct MaxSpeed(pub f32);
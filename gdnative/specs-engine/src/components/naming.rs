@@ -0,0 +1,71 @@
+use specs::prelude::*;
+use std::borrow::Borrow;
+
+/// A set that is backed by a `Vec`. For the handful of values an entity will have, this is faster and smaller than a `HashSet`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SmallSet<T>(Vec<T>);
+
+impl <T: PartialEq> SmallSet<T> {
+    pub fn new() -> Self {
+        Self(Vec::new())
+    }
+    /// Returns false if the value was already in the set.
+    pub fn insert(&mut self, value: T) -> bool {
+        if self.0.contains(&value) {
+            false
+        } else {
+            self.0.push(value);
+            true
+        }
+    }
+    /// Returns false if the value was not in the set.
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+        where T: Borrow<Q>, Q: PartialEq + ?Sized
+    {
+        let len = self.0.len();
+        self.0.retain(|v| v.borrow() != value);
+        len != self.0.len()
+    }
+    pub fn contains<Q>(&self, value: &Q) -> bool
+        where T: Borrow<Q>, Q: PartialEq + ?Sized
+    {
+        self.0.iter().any(|v| v.borrow() == value)
+    }
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.0.iter()
+    }
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl <T: PartialEq> std::iter::FromIterator<T> for SmallSet<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for value in iter {
+            set.insert(value);
+        }
+        set
+    }
+}
+
+/// A name that can be used to look up an entity through the `NameIndex`.
+/// Names are expected to be unique, if two entities share a name the index will only return the most recent one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Name(pub String);
+
+/// Changes are tracked so that the `NameIndexSystem` only needs to update the entities that have changed.
+impl Component for Name {
+    type Storage = FlaggedStorage<Self, DenseVecStorage<Self>>;
+}
+
+/// A set of tags that can be used to look up groups of entities through the `NameIndex`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Tags(pub SmallSet<String>);
+
+impl Component for Tags {
+    type Storage = FlaggedStorage<Self, DenseVecStorage<Self>>;
+}
@@ -0,0 +1,37 @@
+use specs::prelude::*;
+use specs_derive::Component;
+
+/// Moves the entity along a list of waypoints by setting its `SetVelocityIntent`. The path is normally set by the `PathRequestSystem`.
+/// A waypoint is reached once the entity is within `arrive_distance` of it.
+#[derive(Debug, Component)]
+pub struct PathFollow {
+    pub speed: f32,
+    pub arrive_distance: f32,
+    pub (crate) waypoints: Vec<(f32, f32)>,
+    pub (crate) next: usize,
+}
+
+impl PathFollow {
+    pub fn new(speed: f32, arrive_distance: f32) -> Self {
+        Self { speed, arrive_distance, waypoints: Vec::new(), next: 0 }
+    }
+    pub fn set_path(&mut self, waypoints: Vec<(f32, f32)>) {
+        self.waypoints = waypoints;
+        self.next = 0;
+    }
+    pub fn waypoints(&self) -> &[(f32, f32)] {
+        &self.waypoints
+    }
+    pub fn next_waypoint(&self) -> Option<(f32, f32)> {
+        self.waypoints.get(self.next).copied()
+    }
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.waypoints.len()
+    }
+}
+
+/// Sets the `Velocity` of the entity to follow the `FlowField` at `speed`. The entity stops once it reaches a goal.
+#[derive(Debug, Component)]
+pub struct FollowFlowField {
+    pub speed: f32,
+}
@@ -0,0 +1,76 @@
+use std::collections::VecDeque;
+use specs::prelude::*;
+use specs_derive::Component;
+#[cfg(feature = "godot")]
+use gdnative::prelude::*;
+use crate::commands::DeferredCommand;
+
+/// A state of a `StateMachine`. All of the hooks are optional.
+/// The enter and exit hooks run inside of the `StateMachineSystem` so any changes to the world must be returned as a `DeferredCommand`.
+pub trait State: Copy + PartialEq + std::fmt::Debug + Send + Sync + 'static {
+    /// The name that is reported through the `CurrentState` component, eg. for selecting an animation.
+    fn name(&self) -> &'static str;
+    fn on_enter(&self, _entity: Entity) -> Option<DeferredCommand> {
+        None
+    }
+    fn on_exit(&self, _entity: Entity) -> Option<DeferredCommand> {
+        None
+    }
+    /// Called every frame with the number of seconds spent in the state. Returning a state will transition to it.
+    fn on_update(&self, _entity: Entity, _time_in_state: f32) -> Option<Self> {
+        None
+    }
+}
+
+/// A change between two states and the value of `Time::total` when it happened.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StateTransition<S> {
+    pub from: S,
+    pub to: S,
+    pub at: f32,
+}
+
+/// A finite state machine that is advanced by the `StateMachineSystem<S>`. The most recent transitions are kept for debugging.
+#[derive(Debug)]
+pub struct StateMachine<S: State> {
+    pub (crate) current: S,
+    pub (crate) time_in_state: f32,
+    // The enter hook of the initial state runs the first time the system sees the machine.
+    pub (crate) entered: bool,
+    pub (crate) history: VecDeque<StateTransition<S>>,
+    pub (crate) history_capacity: usize,
+}
+
+impl <S: State> Component for StateMachine<S> {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl <S: State> StateMachine<S> {
+    pub fn new(initial: S) -> Self {
+        Self { current: initial, time_in_state: 0.0, entered: false, history: VecDeque::new(), history_capacity: 16 }
+    }
+    /// Sets how many transitions are kept in the history, 0 disables the history.
+    pub fn with_history(mut self, capacity: usize) -> Self {
+        self.history_capacity = capacity;
+        self.history.truncate(capacity);
+        self
+    }
+    pub fn current(&self) -> S {
+        self.current
+    }
+    pub fn time_in_state(&self) -> f32 {
+        self.time_in_state
+    }
+    /// The transitions from oldest to newest.
+    pub fn history(&self) -> impl Iterator<Item = &StateTransition<S>> {
+        self.history.iter()
+    }
+}
+
+/// The name of the current state of the entity's `StateMachine`. This is kept up to date by the `StateMachineSystem` so that it can be read
+/// without knowing the type of the state. If an entity has more than one machine, the name is from whichever changed most recently.
+#[derive(Debug, Clone, Default, PartialEq, Component)]
+#[cfg_attr(feature = "godot", derive(ToVariant))]
+pub struct CurrentState {
+    pub name: String,
+}
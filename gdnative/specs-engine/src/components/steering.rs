@@ -0,0 +1,95 @@
+use specs::prelude::*;
+use specs_derive::Component;
+
+/// Required by any entity that uses steering behaviours. Each behaviour adds a force to the `Steering`, which is then applied to the
+/// `Velocity` by the `ApplySteeringSystem`.
+/// `max_force` limits the total force that can be applied in one second and `max_speed` limits the resulting velocity.
+#[derive(Debug, Component)]
+pub struct Steering {
+    pub max_speed: f32,
+    pub max_force: f32,
+    pub (crate) force: (f32, f32),
+}
+
+impl Steering {
+    pub fn new(max_speed: f32, max_force: f32) -> Self {
+        Self { max_speed, max_force, force: (0.0, 0.0) }
+    }
+    pub fn add_force(&mut self, x: f32, y: f32) {
+        self.force.0 += x;
+        self.force.1 += y;
+    }
+    pub fn force(&self) -> (f32, f32) {
+        self.force
+    }
+}
+
+/// Steers towards the target at full speed.
+#[derive(Debug, Component)]
+pub struct Seek {
+    pub x: f32,
+    pub y: f32,
+}
+
+/// Steers away from the target. If `panic_distance` is set, the entity will only flee while it is inside of that distance.
+#[derive(Debug, Component)]
+pub struct Flee {
+    pub x: f32,
+    pub y: f32,
+    pub panic_distance: Option<f32>,
+}
+
+/// Steers towards the target and slows down once it is inside of the `slowing_radius`, coming to a stop at the target.
+#[derive(Debug, Component)]
+pub struct Arrive {
+    pub x: f32,
+    pub y: f32,
+    pub slowing_radius: f32,
+}
+
+/// Steers towards a target that moves randomly along a circle projected in front of the entity.
+/// `distance` is how far in front the circle is, `radius` is the size of the circle and `jitter` is the maximum change in radians per second.
+#[derive(Debug, Component)]
+pub struct Wander {
+    pub distance: f32,
+    pub radius: f32,
+    pub jitter: f32,
+    pub (crate) angle: f32,
+    pub (crate) seed: u32,
+}
+
+impl Wander {
+    /// `seed` should be different for each entity, otherwise they will all wander the same way.
+    pub fn new(distance: f32, radius: f32, jitter: f32, seed: u32) -> Self {
+        // xorshift cannot use a seed of zero
+        Self { distance, radius, jitter, angle: 0.0, seed: seed.max(1) }
+    }
+    /// Returns a pseudo random number between -1.0 and 1.0 using xorshift.
+    pub (crate) fn next_random(&mut self) -> f32 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 17;
+        self.seed ^= self.seed << 5;
+        (self.seed as f32 / u32::MAX as f32) * 2.0 - 1.0
+    }
+}
+
+/// Boids: steers away from neighbours inside of `radius`, more strongly the closer they are.
+#[derive(Debug, Component)]
+pub struct Separation {
+    pub radius: f32,
+    pub weight: f32,
+}
+
+/// Boids: steers towards the average heading of the neighbours inside of `radius`.
+#[derive(Debug, Component)]
+pub struct Alignment {
+    pub radius: f32,
+    pub weight: f32,
+}
+
+/// Boids: steers towards the average position of the neighbours inside of `radius`.
+#[derive(Debug, Component)]
+pub struct Cohesion {
+    pub radius: f32,
+    pub weight: f32,
+}
@@ -0,0 +1,81 @@
+use specs::prelude::*;
+use specs_derive::Component;
+
+use crate::commands::DeferredCommand;
+use crate::util::WorldCommand;
+
+/// A timer that is advanced by the `TimerSystem` using the `Time` resource.
+/// When `elapsed` reaches `duration` a `TimerFinished` event is emitted with the `tag` and the optional `on_finished` command is deferred.
+/// Repeating timers restart after finishing while one shot timers stay finished until they are reset.
+#[derive(Debug, Component)]
+pub struct Timer {
+    pub duration: f32,
+    pub repeat: bool,
+    pub elapsed: f32,
+    pub tag: &'static str,
+    pub (crate) finished: bool,
+    pub (crate) on_finished: Option<fn(Entity) -> DeferredCommand>,
+}
+
+impl Timer {
+    /// Creates a one shot timer
+    pub fn new(duration: f32, tag: &'static str) -> Self {
+        Self {
+            duration,
+            repeat: false,
+            elapsed: 0.0,
+            tag,
+            finished: false,
+            on_finished: None,
+        }
+    }
+    /// Creates a timer that restarts every time it finishes
+    pub fn repeating(duration: f32, tag: &'static str) -> Self {
+        Self { repeat: true, ..Self::new(duration, tag) }
+    }
+    /// Executes the command `C` with the owning entity each time the timer finishes.
+    /// eg. `Timer::new(5.0, "lifetime").with_command::<DespawnEntity>()`
+    pub fn with_command<C>(mut self) -> Self
+        where C: WorldCommand<Args = Entity> + 'static
+    {
+        self.on_finished = Some(DeferredCommand::new::<C>);
+        self
+    }
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+    pub fn remaining(&self) -> f32 {
+        (self.duration - self.elapsed).max(0.0)
+    }
+    pub fn reset(&mut self) {
+        self.elapsed = 0.0;
+        self.finished = false;
+    }
+}
+
+/// Tracks when an ability or action can be used again.
+/// The cooldown is ready when `remaining` is zero, calling `trigger` will restart it.
+#[derive(Debug, Component)]
+pub struct Cooldown {
+    pub duration: f32,
+    pub remaining: f32,
+}
+
+impl Cooldown {
+    /// Creates a cooldown that is ready to be triggered immediately.
+    pub fn new(duration: f32) -> Self {
+        Self { duration, remaining: 0.0 }
+    }
+    pub fn is_ready(&self) -> bool {
+        self.remaining <= 0.0
+    }
+    /// Restarts the cooldown if it is ready. Returns false if the cooldown was still running.
+    pub fn trigger(&mut self) -> bool {
+        if self.is_ready() {
+            self.remaining = self.duration;
+            true
+        } else {
+            false
+        }
+    }
+}
@@ -0,0 +1,216 @@
+use specs::prelude::*;
+
+/// Easing curves that map the linear progress of a tween (0.0 to 1.0) onto the eased progress.
+/// Reference for the curves: https://easings.net/
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Easing {
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    SineIn,
+    SineOut,
+    SineInOut,
+    BackOut,
+    ElasticOut,
+    BounceOut,
+}
+
+impl Easing {
+    pub fn apply(&self, t: f32) -> f32 {
+        use std::f32::consts::PI;
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::QuadIn => t * t,
+            Easing::QuadOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::QuadInOut => if t < 0.5 { 2.0 * t * t } else { 1.0 - (-2.0 * t + 2.0).powi(2) / 2.0 },
+            Easing::CubicIn => t * t * t,
+            Easing::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Easing::CubicInOut => if t < 0.5 { 4.0 * t * t * t } else { 1.0 - (-2.0 * t + 2.0).powi(3) / 2.0 },
+            Easing::SineIn => 1.0 - (t * PI / 2.0).cos(),
+            Easing::SineOut => (t * PI / 2.0).sin(),
+            Easing::SineInOut => -((PI * t).cos() - 1.0) / 2.0,
+            Easing::BackOut => {
+                const C1: f32 = 1.70158;
+                const C3: f32 = C1 + 1.0;
+                1.0 + C3 * (t - 1.0).powi(3) + C1 * (t - 1.0).powi(2)
+            },
+            Easing::ElasticOut => {
+                if t == 0.0 || t == 1.0 {
+                    t
+                } else {
+                    2f32.powf(-10.0 * t) * ((t * 10.0 - 0.75) * (2.0 * PI / 3.0)).sin() + 1.0
+                }
+            },
+            Easing::BounceOut => {
+                const N1: f32 = 7.5625;
+                const D1: f32 = 2.75;
+                if t < 1.0 / D1 {
+                    N1 * t * t
+                } else if t < 2.0 / D1 {
+                    let t = t - 1.5 / D1;
+                    N1 * t * t + 0.75
+                } else if t < 2.5 / D1 {
+                    let t = t - 2.25 / D1;
+                    N1 * t * t + 0.9375
+                } else {
+                    let t = t - 2.625 / D1;
+                    N1 * t * t + 0.984375
+                }
+            },
+        }
+    }
+}
+
+/// Determines what happens when the last step of a tween has finished.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TweenMode {
+    /// The tween plays once and then stays finished.
+    Once,
+    /// The tween restarts from the first step.
+    Loop,
+    /// The tween plays the steps in reverse and then forwards again.
+    PingPong,
+}
+
+/// A single segment of a tween that animates the property `P` over `duration` seconds.
+#[derive(Debug, Clone)]
+pub struct TweenStep<P> {
+    pub property: P,
+    pub duration: f32,
+    pub easing: Easing,
+}
+
+/// A chain of `TweenStep`s that animate a property of the entity.
+/// `P` describes which property is animated and what the start and end values are, this allows the same timing logic to be shared by
+/// any system that knows how to apply `P` such as the `TransformTweenSystem`.
+#[derive(Debug)]
+pub struct Tween<P> {
+    pub steps: Vec<TweenStep<P>>,
+    pub mode: TweenMode,
+    pub tag: &'static str,
+    current: usize,
+    elapsed: f32,
+    forward: bool,
+    finished: bool,
+}
+
+impl <P: Send + Sync + 'static> Component for Tween<P> {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl <P> Tween<P> {
+    pub fn new(property: P, duration: f32, easing: Easing) -> Self {
+        Self {
+            steps: vec![TweenStep { property, duration, easing }],
+            mode: TweenMode::Once,
+            tag: "",
+            current: 0,
+            elapsed: 0.0,
+            forward: true,
+            finished: false,
+        }
+    }
+    /// Chains another step that starts once the previous step has finished.
+    pub fn then(mut self, property: P, duration: f32, easing: Easing) -> Self {
+        self.steps.push(TweenStep { property, duration, easing });
+        self
+    }
+    pub fn with_mode(mut self, mode: TweenMode) -> Self {
+        self.mode = mode;
+        self
+    }
+    /// The tag is passed along with the `TweenFinished` event.
+    pub fn with_tag(mut self, tag: &'static str) -> Self {
+        self.tag = tag;
+        self
+    }
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Advances the tween by `delta` seconds and calls `apply` with the property and the eased progress of every step that changed.
+    /// Returns true when the end of the chain was reached. Looping tweens will return true once per cycle and ping-pong tweens will
+    /// return true at both ends.
+    pub fn advance<F: FnMut(&P, f32)>(&mut self, delta: f32, mut apply: F) -> bool {
+        if self.finished || self.steps.is_empty() {
+            return false;
+        }
+        let mut remaining = delta;
+        loop {
+            let step = &self.steps[self.current];
+            let duration = step.duration.max(f32::EPSILON);
+            if self.elapsed + remaining < duration {
+                self.elapsed += remaining;
+                let t = self.elapsed / duration;
+                apply(&step.property, step.easing.apply(if self.forward { t } else { 1.0 - t }));
+                return false;
+            }
+            // The step has finished, so make sure the property ends up at exactly the end value.
+            remaining -= duration - self.elapsed;
+            self.elapsed = 0.0;
+            apply(&step.property, if self.forward { 1.0 } else { 0.0 });
+            if self.forward && self.current + 1 < self.steps.len() {
+                self.current += 1;
+            } else if !self.forward && self.current > 0 {
+                self.current -= 1;
+            } else {
+                // The end of the chain has been reached. Any time that is left over is dropped so that a very large delta cannot loop forever.
+                match self.mode {
+                    TweenMode::Once => self.finished = true,
+                    TweenMode::Loop => self.current = 0,
+                    TweenMode::PingPong => self.forward = !self.forward,
+                }
+                return true;
+            }
+        }
+    }
+}
+
+/// The transform properties that can be animated by the `TransformTweenSystem`.
+#[derive(Debug, Clone, Copy)]
+pub enum TransformProperty {
+    Position { from: (f32, f32), to: (f32, f32) },
+    Scale { from: (f32, f32), to: (f32, f32) },
+    Rotation { from: f32, to: f32 },
+}
+
+pub type TransformTween = Tween<TransformProperty>;
+
+/// Linearly interpolates between `from` and `to`. `t` is not clamped to allow easing curves that overshoot.
+pub fn lerp(from: f32, to: f32, t: f32) -> f32 {
+    from + (to - from) * t
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_tween_chain() {
+        let mut tween = Tween::new(0.0f32, 1.0, Easing::Linear).then(10.0, 1.0, Easing::Linear);
+        let mut applied = Vec::new();
+        assert!(!tween.advance(1.5, |p, t| applied.push((*p, t))));
+        assert_eq!(applied, vec![(0.0, 1.0), (10.0, 0.5)]);
+        assert!(tween.advance(0.5, |_, _| {}));
+        assert!(tween.is_finished());
+        assert!(!tween.advance(0.5, |_, _| panic!("a finished tween should not apply anything")));
+    }
+
+    #[test]
+    pub fn test_tween_ping_pong() {
+        let mut tween = Tween::new((), 1.0, Easing::Linear).with_mode(TweenMode::PingPong);
+        let mut progress = 0.0;
+        assert!(tween.advance(1.0, |_, t| progress = t));
+        assert_eq!(progress, 1.0);
+        assert!(!tween.advance(0.25, |_, t| progress = t));
+        assert_eq!(progress, 0.75);
+        assert!(tween.advance(0.75, |_, t| progress = t));
+        assert_eq!(progress, 0.0);
+        assert!(!tween.is_finished());
+    }
+}
@@ -0,0 +1,30 @@
+use std::sync::Arc;
+use specs::prelude::*;
+use specs_derive::Component;
+use crate::utility::UtilityReasoner;
+
+/// Scores the actions of the reasoner every `interval` frames and writes the best one into the entity's `Intent`.
+#[derive(Debug, Component)]
+pub struct UtilityAi {
+    pub reasoner: Arc<UtilityReasoner>,
+    pub interval: u32,
+    pub (crate) frames_until_update: u32,
+}
+
+impl UtilityAi {
+    pub fn new(reasoner: Arc<UtilityReasoner>, interval: u32) -> Self {
+        Self { reasoner, interval: interval.max(1), frames_until_update: 0 }
+    }
+    /// Delays the first update so that entities that are created together do not all update on the same frame.
+    pub fn with_offset(mut self, frames: u32) -> Self {
+        self.frames_until_update = frames % self.interval;
+        self
+    }
+}
+
+/// Indicates that the entity wants to perform the named action. This is written by the `UtilitySystem` and acted on by other systems.
+#[derive(Debug, Clone, PartialEq, Component)]
+pub struct Intent {
+    pub action: &'static str,
+    pub score: f32,
+}
@@ -0,0 +1,5 @@
+//! This contains the components and systems for common gameplay rules: health, damage and stats that can be modified over time.
+mod damage;
+mod stats;
+pub use damage::*;
+pub use stats::*;
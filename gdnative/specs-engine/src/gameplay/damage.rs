@@ -0,0 +1,154 @@
+use specs::prelude::*;
+use specs_derive::Component;
+#[cfg(feature = "godot")]
+use gdnative::prelude::*;
+use crate::components::DespawnRequest;
+use crate::gameplay::Stats;
+use crate::resources::WorldMsgQueue;
+
+#[derive(Debug, Component)]
+#[cfg_attr(feature = "godot", derive(ToVariant))]
+pub struct Health {
+    pub current: f32,
+    pub max: f32,
+}
+
+impl Health {
+    pub fn new(max: f32) -> Self {
+        Self { current: max, max }
+    }
+    pub fn is_dead(&self) -> bool {
+        self.current <= 0.0
+    }
+    /// Heals up to the maximum health, the dead cannot be healed.
+    pub fn heal(&mut self, amount: f32) {
+        if !self.is_dead() {
+            self.current = (self.current + amount).min(self.max);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DamageType {
+    Physical,
+    Fire,
+    Cold,
+    Poison,
+    /// Ignores all resistances.
+    True,
+}
+
+impl DamageType {
+    /// The `Stats` entry that reduces this type of damage. A resistance of 0.25 reduces the damage by 25%,
+    /// negative resistances increase the damage.
+    pub fn resistance_stat(&self) -> Option<&'static str> {
+        match self {
+            DamageType::Physical => Some("physical_resistance"),
+            DamageType::Fire => Some("fire_resistance"),
+            DamageType::Cold => Some("cold_resistance"),
+            DamageType::Poison => Some("poison_resistance"),
+            DamageType::True => None,
+        }
+    }
+}
+
+/// The damage that this entity deals, eg. to whatever a projectile hits.
+#[derive(Debug, Clone, Copy, Component)]
+pub struct Damage {
+    pub amount: f32,
+    pub damage_type: DamageType,
+}
+
+impl Damage {
+    pub fn to(&self, source: Entity, target: Entity) -> DamageEvent {
+        DamageEvent { target, source: Some(source), amount: self.amount, damage_type: self.damage_type }
+    }
+}
+
+/// A request to damage the target. Any system may push these, they are all applied by the `DamageSystem`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DamageEvent {
+    pub target: Entity,
+    pub source: Option<Entity>,
+    pub amount: f32,
+    pub damage_type: DamageType,
+}
+
+/// Emitted when an entity's `Health` reaches 0. The second value is the source of the damage that killed it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Died(pub Entity, pub Option<Entity>);
+
+/// Applies all of the queued `DamageEvent`s. The events are sorted before they are applied so that the outcome, such as which source
+/// gets credited with a kill, does not depend on the order that parallel systems pushed them.
+/// Entities that die emit a `Died` event and request a despawn, any further damage to them is ignored.
+// Note: Both the `WorldMsgQueue<DamageEvent>` and `WorldMsgQueue<Died>` resources MUST be added to the simulation for this system to work.
+pub struct DamageSystem {}
+
+impl <'a> System <'a> for DamageSystem {
+    type SystemData = (
+        WriteExpect<'a, WorldMsgQueue<DamageEvent>>,
+        ReadExpect<'a, WorldMsgQueue<Died>>,
+        ReadStorage<'a, Stats>,
+        WriteStorage<'a, Health>,
+        WriteStorage<'a, DespawnRequest>,
+    );
+    fn run(&mut self, data: Self::SystemData) {
+        let (damage_events, died, stats, mut healths, mut despawn_requests) = data;
+        let events = damage_events.drain_sorted_by_key(|event| {
+            (event.target.id(), event.source.map(|source| source.id()), event.damage_type, event.amount.to_bits())
+        });
+        for event in events {
+            let health = match healths.get_mut(event.target) {
+                Some(health) if !health.is_dead() => health,
+                _ => continue,
+            };
+            let resistance = event.damage_type
+                .resistance_stat()
+                .and_then(|stat| stats.get(event.target).map(|stats| stats.get(stat)))
+                .unwrap_or(0.0)
+                .min(1.0);
+            health.current -= event.amount.max(0.0) * (1.0 - resistance);
+            if health.is_dead() {
+                died.push(Died(event.target, event.source));
+                if let Err(err) = despawn_requests.insert(event.target, DespawnRequest) {
+                    log::warn!("could not despawn {:?}: {}", event.target, err);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::resources::Time;
+
+    fn create_world() -> World {
+        let mut world = World::new();
+        crate::register_components(&mut world);
+        world.insert(Time { delta: 1.0, total: 0.0 });
+        world.insert(WorldMsgQueue::<DamageEvent>::new());
+        world.insert(WorldMsgQueue::<Died>::new());
+        world
+    }
+
+    #[test]
+    pub fn test_resistance_and_death() {
+        let mut world = create_world();
+        let first = world.create_entity().build();
+        let second = world.create_entity().build();
+        let target = world.create_entity().with(Health::new(10.0)).with(Stats::new().with_base("fire_resistance", 0.5)).build();
+        let fire = Damage { amount: 10.0, damage_type: DamageType::Fire };
+        world.fetch::<WorldMsgQueue<DamageEvent>>().push(fire.to(first, target));
+        DamageSystem {}.run_now(&world);
+        assert_eq!(world.read_storage::<Health>().get(target).unwrap().current, 5.0);
+
+        // Both sources deal lethal damage in the same frame, the kill always goes to the source with the lowest id.
+        world.fetch::<WorldMsgQueue<DamageEvent>>().push(fire.to(second, target));
+        world.fetch::<WorldMsgQueue<DamageEvent>>().push(fire.to(first, target));
+        DamageSystem {}.run_now(&world);
+        assert_eq!(world.fetch::<WorldMsgQueue<Died>>().pop(), Some(Died(target, Some(first))));
+        assert!(world.fetch::<WorldMsgQueue<Died>>().pop().is_none());
+        assert!(world.read_storage::<DespawnRequest>().contains(target));
+    }
+}
@@ -0,0 +1,126 @@
+use specs::prelude::*;
+use specs_derive::Component;
+use std::collections::HashMap;
+use crate::resources::Time;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ModifierKind {
+    /// Added to the base value.
+    Additive(f32),
+    /// Multiplies the value after all of the additive modifiers have been applied.
+    Multiplicative(f32),
+}
+
+/// Changes a stat until it expires. `remaining` is the number of seconds left, or `None` if it lasts until it is removed.
+/// `source` can be used to find and remove the modifiers that were added by something, eg. an item or spell.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Modifier {
+    pub stat: &'static str,
+    pub kind: ModifierKind,
+    pub remaining: Option<f32>,
+    pub source: &'static str,
+}
+
+impl Modifier {
+    pub fn additive(stat: &'static str, amount: f32, source: &'static str) -> Self {
+        Self { stat, kind: ModifierKind::Additive(amount), remaining: None, source }
+    }
+    pub fn multiplicative(stat: &'static str, factor: f32, source: &'static str) -> Self {
+        Self { stat, kind: ModifierKind::Multiplicative(factor), remaining: None, source }
+    }
+    pub fn lasting(mut self, seconds: f32) -> Self {
+        self.remaining = Some(seconds);
+        self
+    }
+}
+
+/// Named stats with a base value and any number of modifiers. A stat without a base value is 0.
+#[derive(Debug, Clone, Default, Component)]
+pub struct Stats {
+    base: HashMap<&'static str, f32>,
+    modifiers: Vec<Modifier>,
+}
+
+impl Stats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn with_base(mut self, stat: &'static str, value: f32) -> Self {
+        self.base.insert(stat, value);
+        self
+    }
+    pub fn set_base(&mut self, stat: &'static str, value: f32) {
+        self.base.insert(stat, value);
+    }
+    pub fn base(&self, stat: &str) -> f32 {
+        self.base.get(stat).copied().unwrap_or(0.0)
+    }
+    /// The base value with every modifier applied, `(base + additive) * multiplicative`.
+    pub fn get(&self, stat: &str) -> f32 {
+        let mut additive = 0.0;
+        let mut multiplicative = 1.0;
+        for modifier in self.modifiers.iter().filter(|modifier| modifier.stat == stat) {
+            match modifier.kind {
+                ModifierKind::Additive(amount) => additive += amount,
+                ModifierKind::Multiplicative(factor) => multiplicative *= factor,
+            }
+        }
+        (self.base(stat) + additive) * multiplicative
+    }
+    pub fn add_modifier(&mut self, modifier: Modifier) {
+        self.modifiers.push(modifier);
+    }
+    /// Removes every modifier from the source and returns how many were removed.
+    pub fn remove_modifiers_from(&mut self, source: &str) -> usize {
+        let len = self.modifiers.len();
+        self.modifiers.retain(|modifier| modifier.source != source);
+        len - self.modifiers.len()
+    }
+    pub fn modifiers(&self) -> &[Modifier] {
+        &self.modifiers
+    }
+}
+
+/// Counts down the timed modifiers of every `Stats` and removes them once they expire.
+pub struct ModifierSystem {}
+
+impl <'a> System <'a> for ModifierSystem {
+    type SystemData = (
+        ReadExpect<'a, Time>,
+        WriteStorage<'a, Stats>,
+    );
+    fn run(&mut self, data: Self::SystemData) {
+        let (time, mut stats) = data;
+        (&mut stats).par_join().for_each(|stats| {
+            for modifier in stats.modifiers.iter_mut() {
+                if let Some(remaining) = modifier.remaining.as_mut() {
+                    *remaining -= time.delta;
+                }
+            }
+            stats.modifiers.retain(|modifier| modifier.remaining.is_none_or(|remaining| remaining > 0.0));
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_timed_modifiers() {
+        let mut world = World::new();
+        crate::register_components(&mut world);
+        world.insert(Time { delta: 1.0, total: 0.0 });
+        let mut stats = Stats::new().with_base("speed", 10.0);
+        stats.add_modifier(Modifier::additive("speed", 5.0, "boots"));
+        stats.add_modifier(Modifier::multiplicative("speed", 0.5, "slow").lasting(1.5));
+        let entity = world.create_entity().with(stats).build();
+        assert_eq!(world.read_storage::<Stats>().get(entity).unwrap().get("speed"), 7.5);
+        ModifierSystem {}.run_now(&world);
+        assert_eq!(world.read_storage::<Stats>().get(entity).unwrap().get("speed"), 7.5);
+        ModifierSystem {}.run_now(&world);
+        assert_eq!(world.read_storage::<Stats>().get(entity).unwrap().get("speed"), 15.0);
+        assert_eq!(world.write_storage::<Stats>().get_mut(entity).unwrap().remove_modifiers_from("boots"), 1);
+        assert_eq!(world.read_storage::<Stats>().get(entity).unwrap().get("speed"), 10.0);
+    }
+}
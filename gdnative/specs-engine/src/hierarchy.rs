@@ -0,0 +1,234 @@
+//! This contains the commands and queries used to keep the `TreeRelationship` between entities consistent.
+//! Both the parent and the children hold references to each other, so any change to the tree must update both sides.
+use specs::prelude::*;
+use crate::components::{DespawnRequest, TreeRelationship};
+use crate::util::{WorldCommand, WorldQuery};
+
+/// Requests a despawn for the entity and all of its descendants. The entity is removed from its parent immediately so that the parent
+/// never references an entity that is about to be deleted. Returns the number of entities that will be despawned.
+pub struct DespawnRecursive {}
+
+impl WorldCommand for DespawnRecursive {
+    type Args = Entity;
+    type Output = usize;
+    fn execute(world: &mut World, entity: Self::Args) -> Self::Output {
+        let entities = world.entities();
+        let mut relationships = world.write_storage::<TreeRelationship>();
+        let mut despawn_requests = world.write_storage::<DespawnRequest>();
+        if !entities.is_alive(entity) {
+            log::warn!("cannot despawn {:?} as it is not alive", entity);
+            return 0;
+        }
+        detach_from_parent(&mut relationships, entity);
+        let mut despawned = 0;
+        let mut pending = vec![entity];
+        while let Some(entity) = pending.pop() {
+            if !entities.is_alive(entity) {
+                continue;
+            }
+            if despawn_requests.insert(entity, DespawnRequest).expect("the entity should be alive").is_none() {
+                despawned += 1;
+            }
+            if let Some(relationship) = relationships.get(entity) {
+                pending.extend(relationship.children.iter().copied());
+            }
+        }
+        despawned
+    }
+}
+
+/// Moves the entity underneath a new parent, or makes it a root if the new parent is `None`.
+/// Entities that do not yet have a `TreeRelationship` will have one added.
+/// Fails if the new parent is not alive or if it would create a cycle.
+pub struct Reparent {}
+
+impl WorldCommand for Reparent {
+    type Args = (Entity, Option<Entity>);
+    type Output = Result<(), &'static str>;
+    fn execute(world: &mut World, args: Self::Args) -> Self::Output {
+        let (entity, new_parent) = args;
+        let entities = world.entities();
+        let mut relationships = world.write_storage::<TreeRelationship>();
+        if !entities.is_alive(entity) {
+            return Err("entity is not alive");
+        }
+        if let Some(new_parent) = new_parent {
+            if !entities.is_alive(new_parent) {
+                return Err("new parent is not alive");
+            }
+            // Walk up from the new parent, if the entity is found then it would become its own ancestor.
+            let mut ancestor = Some(new_parent);
+            while let Some(current) = ancestor {
+                if current == entity {
+                    return Err("entity cannot be parented to itself or one of its descendants");
+                }
+                ancestor = relationships.get(current).and_then(|relationship| relationship.parent);
+            }
+        }
+        detach_from_parent(&mut relationships, entity);
+        if let Some(new_parent) = new_parent {
+            let parent_relationship = relationships
+                .entry(new_parent)
+                .expect("the parent should be alive")
+                .or_insert_with(TreeRelationship::default);
+            parent_relationship.children.push(entity);
+        }
+        relationships
+            .entry(entity)
+            .expect("the entity should be alive")
+            .or_insert_with(TreeRelationship::default)
+            .parent = new_parent;
+        Ok(())
+    }
+}
+
+/// Removes the entity from the children of its parent and makes it a root.
+pub(crate) fn detach_from_parent(relationships: &mut WriteStorage<TreeRelationship>, entity: Entity) {
+    let parent = relationships.get_mut(entity).and_then(|relationship| relationship.parent.take());
+    if let Some(parent) = parent {
+        if let Some(parent_relationship) = relationships.get_mut(parent) {
+            parent_relationship.children.retain(|child| *child != entity);
+        }
+    }
+}
+
+/// A link in the `TreeRelationship` that does not match the other side of the relationship.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BrokenLink {
+    /// The parent of `entity` has been deleted.
+    DeadParent { entity: Entity, parent: Entity },
+    /// A child of `entity` has been deleted.
+    DeadChild { entity: Entity, child: Entity },
+    /// `entity` references `parent`, but is not one of its children.
+    NotAChildOfParent { entity: Entity, parent: Entity },
+    /// `entity` lists `child` as a child, but the child has a different parent.
+    NotAParentOfChild { entity: Entity, child: Entity },
+}
+
+/// Reports every broken link in the `TreeRelationship`s of the world. An empty list means that the tree is consistent.
+pub struct ValidateHierarchy {}
+
+impl WorldQuery for ValidateHierarchy {
+    type Args = ();
+    type Output = Vec<BrokenLink>;
+    fn query(world: &World, _: Self::Args) -> Self::Output {
+        let entities = world.entities();
+        let relationships = world.read_storage::<TreeRelationship>();
+        let mut broken = Vec::new();
+        for (entity, relationship) in (&entities, &relationships).join() {
+            if let Some(parent) = relationship.parent {
+                if !entities.is_alive(parent) {
+                    broken.push(BrokenLink::DeadParent { entity, parent });
+                } else if !relationships.get(parent).is_some_and(|p| p.children.contains(&entity)) {
+                    broken.push(BrokenLink::NotAChildOfParent { entity, parent });
+                }
+            }
+            for child in relationship.children.iter().copied() {
+                if !entities.is_alive(child) {
+                    broken.push(BrokenLink::DeadChild { entity, child });
+                } else if relationships.get(child).and_then(|c| c.parent) != Some(entity) {
+                    broken.push(BrokenLink::NotAParentOfChild { entity, child });
+                }
+            }
+        }
+        broken
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::systems::{DespawnSystem, PropagateDespawnSystem};
+
+    fn create_world() -> World {
+        let mut world = World::new();
+        crate::register_components(&mut world);
+        world
+    }
+
+    fn create_child(world: &mut World, parent: Entity) -> Entity {
+        let child = world.create_entity().build();
+        Reparent::execute(world, (child, Some(parent))).expect("reparent should succeed");
+        child
+    }
+
+    fn run_despawn(world: &mut World) {
+        PropagateDespawnSystem {}.run_now(world);
+        DespawnSystem {}.run_now(world);
+        world.maintain();
+    }
+
+    #[test]
+    pub fn test_despawn_recursive() {
+        let mut world = create_world();
+        let root = world.create_entity().build();
+        let parent = create_child(&mut world, root);
+        let child = create_child(&mut world, parent);
+        let grandchild = create_child(&mut world, child);
+
+        assert_eq!(DespawnRecursive::execute(&mut world, parent), 3);
+        run_despawn(&mut world);
+
+        assert!(world.is_alive(root));
+        assert!(!world.is_alive(parent));
+        assert!(!world.is_alive(child));
+        assert!(!world.is_alive(grandchild));
+        assert!(world.read_storage::<TreeRelationship>().get(root).unwrap().children.is_empty());
+        assert!(ValidateHierarchy::query(&world, ()).is_empty());
+    }
+
+    #[test]
+    pub fn test_reparent() {
+        let mut world = create_world();
+        let first = world.create_entity().build();
+        let second = world.create_entity().build();
+        let child = create_child(&mut world, first);
+
+        Reparent::execute(&mut world, (child, Some(second))).expect("reparent should succeed");
+        {
+            let relationships = world.read_storage::<TreeRelationship>();
+            assert!(relationships.get(first).unwrap().children.is_empty());
+            assert_eq!(relationships.get(second).unwrap().children, vec![child]);
+            assert_eq!(relationships.get(child).unwrap().parent, Some(second));
+        }
+        Reparent::execute(&mut world, (child, None)).expect("reparent should succeed");
+        assert!(world.read_storage::<TreeRelationship>().get(second).unwrap().children.is_empty());
+        assert!(ValidateHierarchy::query(&world, ()).is_empty());
+    }
+
+    #[test]
+    pub fn test_reparent_cycle() {
+        let mut world = create_world();
+        let parent = world.create_entity().build();
+        let child = create_child(&mut world, parent);
+        let grandchild = create_child(&mut world, child);
+        assert!(Reparent::execute(&mut world, (parent, Some(grandchild))).is_err());
+        assert!(Reparent::execute(&mut world, (parent, Some(parent))).is_err());
+        assert!(ValidateHierarchy::query(&world, ()).is_empty());
+    }
+
+    #[test]
+    pub fn test_despawn_detaches_from_parent() {
+        let mut world = create_world();
+        let parent = world.create_entity().build();
+        let child = create_child(&mut world, parent);
+        world.write_storage::<DespawnRequest>().insert(child, DespawnRequest).unwrap();
+        run_despawn(&mut world);
+        assert!(world.read_storage::<TreeRelationship>().get(parent).unwrap().children.is_empty());
+        assert!(ValidateHierarchy::query(&world, ()).is_empty());
+    }
+
+    #[test]
+    pub fn test_validate_hierarchy() {
+        let mut world = create_world();
+        let parent = world.create_entity().build();
+        let child = create_child(&mut world, parent);
+        // Deleting the child directly bypasses the despawn pipeline and leaves the parent with a dangling reference.
+        world.delete_entity(child).unwrap();
+        let stranger = world.create_entity().with(TreeRelationship { parent: Some(parent), children: Vec::new() }).build();
+        let broken = ValidateHierarchy::query(&world, ());
+        assert_eq!(broken.len(), 2);
+        assert!(broken.contains(&BrokenLink::DeadChild { entity: parent, child }));
+        assert!(broken.contains(&BrokenLink::NotAChildOfParent { entity: stranger, parent }));
+    }
+}
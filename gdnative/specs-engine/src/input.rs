@@ -0,0 +1,165 @@
+//! This contains the `InputState` resource that systems read player input from, and the `ActionMap` used to fill it.
+//! The state does not depend on Godot, so tests can set the actions directly instead of using an `ActionMap`.
+use std::collections::HashMap;
+
+/// The state of a single action for the current frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ActionState {
+    pub strength: f32,
+    pub pressed: bool,
+    pub just_pressed: bool,
+    pub just_released: bool,
+}
+
+/// The actions and axes for the current frame. Each action should be set once per frame so that `just_pressed` and `just_released`
+/// are only true for the frame where the action changed.
+#[derive(Debug, Clone, Default)]
+pub struct InputState {
+    actions: HashMap<String, ActionState>,
+    axes: HashMap<String, f32>,
+    pointer: Option<(f32, f32)>,
+}
+
+impl InputState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Sets the strength of the action between 0.0 and 1.0, the action is pressed if the strength is above 0.
+    pub fn set_action(&mut self, action: &str, strength: f32) {
+        let strength = strength.clamp(0.0, 1.0);
+        let pressed = strength > 0.0;
+        let state = self.actions.entry(action.to_owned()).or_default();
+        state.just_pressed = pressed && !state.pressed;
+        state.just_released = !pressed && state.pressed;
+        state.pressed = pressed;
+        state.strength = strength;
+    }
+    pub fn action(&self, action: &str) -> ActionState {
+        self.actions.get(action).copied().unwrap_or_default()
+    }
+    pub fn is_pressed(&self, action: &str) -> bool {
+        self.action(action).pressed
+    }
+    pub fn just_pressed(&self, action: &str) -> bool {
+        self.action(action).just_pressed
+    }
+    pub fn just_released(&self, action: &str) -> bool {
+        self.action(action).just_released
+    }
+    pub fn strength(&self, action: &str) -> f32 {
+        self.action(action).strength
+    }
+    /// Sets the value of the axis between -1.0 and 1.0.
+    pub fn set_axis(&mut self, axis: &str, value: f32) {
+        self.axes.insert(axis.to_owned(), value.clamp(-1.0, 1.0));
+    }
+    pub fn axis(&self, axis: &str) -> f32 {
+        self.axes.get(axis).copied().unwrap_or(0.0)
+    }
+    /// The position of the mouse or touch in world space, if it has been reported. The Godot worlds convert the position from the viewport
+    /// with the canvas transform, or with the `CameraView` if there is one.
+    pub fn pointer(&self) -> Option<(f32, f32)> {
+        self.pointer
+    }
+    pub fn set_pointer(&mut self, x: f32, y: f32) {
+        self.pointer = Some((x, y));
+    }
+}
+
+/// Maps the actions and axes used by the game onto bindings, such as the names of the actions in Godot's `InputMap`.
+/// An action is as strong as its strongest binding and an axis is the strength of its positive binding minus its negative binding.
+///
+/// The config has one binding per line, blank lines and lines starting with `#` are ignored:
+/// ```text
+/// action jump = ui_accept, jump
+/// axis move_x = ui_left, ui_right
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ActionMap {
+    actions: HashMap<String, Vec<String>>,
+    axes: HashMap<String, (String, String)>,
+}
+
+impl ActionMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn from_config(config: &str) -> Result<Self, String> {
+        let mut map = Self::new();
+        for (number, line) in config.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = || format!("line {} is not a valid binding: {}", number + 1, line);
+            let (name, bindings) = match line.find('=') {
+                Some(index) => (line[..index].trim(), line[index + 1..].trim()),
+                None => return Err(error()),
+            };
+            let bindings: Vec<String> = bindings.split(',').map(|binding| binding.trim().to_owned()).filter(|binding| !binding.is_empty()).collect();
+            let mut words = name.split_whitespace();
+            match (words.next(), words.next(), words.next()) {
+                (Some("action"), Some(action), None) => map.bind_action(action, bindings),
+                (Some("axis"), Some(axis), None) if bindings.len() == 2 => map.bind_axis(axis, &bindings[0], &bindings[1]),
+                _ => return Err(error()),
+            }
+        }
+        Ok(map)
+    }
+    /// Writes the map in the format read by `from_config` so that rebound actions can be saved.
+    pub fn to_config(&self) -> String {
+        let mut lines: Vec<String> = self.actions.iter()
+            .map(|(action, bindings)| format!("action {} = {}", action, bindings.join(", ")))
+            .chain(self.axes.iter().map(|(axis, (negative, positive))| format!("axis {} = {}, {}", axis, negative, positive)))
+            .collect();
+        lines.sort();
+        lines.join("\n")
+    }
+    /// Replaces the bindings of the action.
+    pub fn bind_action(&mut self, action: &str, bindings: Vec<String>) {
+        self.actions.insert(action.to_owned(), bindings);
+    }
+    pub fn bind_axis(&mut self, axis: &str, negative: &str, positive: &str) {
+        self.axes.insert(axis.to_owned(), (negative.to_owned(), positive.to_owned()));
+    }
+    pub fn bindings(&self, action: &str) -> &[String] {
+        self.actions.get(action).map(|bindings| bindings.as_slice()).unwrap_or(&[])
+    }
+    /// Updates every action and axis in the state. `strength_of` returns the strength of a binding between 0.0 and 1.0.
+    pub fn update<F: Fn(&str) -> f32>(&self, state: &mut InputState, strength_of: F) {
+        for (action, bindings) in self.actions.iter() {
+            let strength = bindings.iter().map(|binding| strength_of(binding)).fold(0.0, f32::max);
+            state.set_action(action, strength);
+        }
+        for (axis, (negative, positive)) in self.axes.iter() {
+            state.set_axis(axis, strength_of(positive) - strength_of(negative));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_action_map() {
+        let config = "
+            # movement
+            axis move_x = left, right
+            action jump = space, pad_a
+        ";
+        let map = ActionMap::from_config(config).expect("the config should be valid");
+        assert_eq!(ActionMap::from_config(&map.to_config()), Ok(map.clone()));
+        assert!(ActionMap::from_config("axis move_x = left").is_err());
+
+        let mut state = InputState::new();
+        map.update(&mut state, |binding| if binding == "right" || binding == "pad_a" { 1.0 } else { 0.0 });
+        assert_eq!(state.axis("move_x"), 1.0);
+        assert!(state.just_pressed("jump"));
+        map.update(&mut state, |binding| if binding == "right" || binding == "pad_a" { 1.0 } else { 0.0 });
+        assert!(state.is_pressed("jump"));
+        assert!(!state.just_pressed("jump"));
+        map.update(&mut state, |_| 0.0);
+        assert!(state.just_released("jump"));
+    }
+}
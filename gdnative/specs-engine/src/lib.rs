@@ -1,14 +1,32 @@
 //! The ECS crate that contains all of the ECS specific implementation details.
 
+mod behavior;
+mod commands;
 mod components;
+mod gameplay;
+mod hierarchy;
+mod input;
+mod naming;
+#[cfg(feature = "rapier")]
+mod rapier;
 mod resources;
 mod systems;
 mod util;
+mod utility;
 
+pub use behavior::*;
+pub use commands::*;
 pub use components::*;
+pub use gameplay::*;
+pub use hierarchy::*;
+pub use input::*;
+pub use naming::*;
+#[cfg(feature = "rapier")]
+pub use rapier::*;
 pub use resources::*;
 pub use systems::*;
 pub use util::*;
+pub use utility::*;
 
 #[cfg(test)]
 static TEST_LOGGER_INIT: std::sync::Once = std::sync::Once::new();
@@ -0,0 +1,84 @@
+//! This contains the `NameIndex` resource and the queries used to find entities by their `Name` or `Tags`.
+use specs::prelude::*;
+use specs::world::Index;
+use std::collections::HashMap;
+use crate::components::Tags;
+use crate::util::WorldQuery;
+
+/// An index of the `Name` and `Tags` components that is kept up to date by the `NameIndexSystem`.
+/// As the index is only updated when the system runs, any changes made since the last run will not be visible.
+#[derive(Debug, Default)]
+pub struct NameIndex {
+    names: HashMap<String, Entity>,
+    tags: HashMap<String, Vec<Entity>>,
+    // When a component is removed it can no longer be read, so the indexed values are also stored by the entity's index.
+    entity_names: HashMap<Index, (Entity, String)>,
+    entity_tags: HashMap<Index, (Entity, Vec<String>)>,
+}
+
+impl NameIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn find_by_name(&self, name: &str) -> Option<Entity> {
+        self.names.get(name).copied()
+    }
+    pub fn find_by_tag(&self, tag: &str) -> &[Entity] {
+        self.tags.get(tag).map(|entities| entities.as_slice()).unwrap_or(&[])
+    }
+    pub (crate) fn set_name(&mut self, entity: Entity, name: &str) {
+        self.remove_name(entity.id());
+        if let Some(previous) = self.names.insert(name.to_owned(), entity) {
+            log::warn!("{:?} and {:?} share the name {}, only {:?} can be found by name", previous, entity, name, entity);
+        }
+        self.entity_names.insert(entity.id(), (entity, name.to_owned()));
+    }
+    pub (crate) fn remove_name(&mut self, index: Index) {
+        if let Some((entity, name)) = self.entity_names.remove(&index) {
+            if self.names.get(&name) == Some(&entity) {
+                self.names.remove(&name);
+            }
+        }
+    }
+    pub (crate) fn set_tags(&mut self, entity: Entity, tags: &Tags) {
+        self.remove_tags(entity.id());
+        for tag in tags.0.iter() {
+            self.tags.entry(tag.clone()).or_default().push(entity);
+        }
+        self.entity_tags.insert(entity.id(), (entity, tags.0.iter().cloned().collect()));
+    }
+    pub (crate) fn remove_tags(&mut self, index: Index) {
+        if let Some((entity, tags)) = self.entity_tags.remove(&index) {
+            for tag in tags {
+                if let Some(entities) = self.tags.get_mut(&tag) {
+                    entities.retain(|e| *e != entity);
+                    if entities.is_empty() {
+                        self.tags.remove(&tag);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Finds the entity with the given `Name`.
+pub struct FindByName {}
+
+impl WorldQuery for FindByName {
+    type Args = String;
+    type Output = Option<Entity>;
+    fn query(world: &World, name: Self::Args) -> Self::Output {
+        world.try_fetch::<NameIndex>().and_then(|index| index.find_by_name(&name))
+    }
+}
+
+/// Finds all of the entities that have the tag in their `Tags`.
+pub struct FindByTag {}
+
+impl WorldQuery for FindByTag {
+    type Args = String;
+    type Output = Vec<Entity>;
+    fn query(world: &World, tag: Self::Args) -> Self::Output {
+        world.try_fetch::<NameIndex>().map(|index| index.find_by_tag(&tag).to_vec()).unwrap_or_default()
+    }
+}
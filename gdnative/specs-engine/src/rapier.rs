@@ -1,4 +1,7 @@
 use rapier2d::prelude::*;
+use specs::prelude::*;
+use specs_derive::Component;
+use crate::components::DespawnRequest;
 /// This is a container for all of the Physics related structs that will be injected into the relevant World
 pub struct RapierPhysicsResource {
     pub (crate) gravity: Vector<f32>,
@@ -13,8 +16,8 @@ pub struct RapierPhysicsResource {
     pipeline: PhysicsPipeline,
 }
 
-impl RapierPhysicsResource {
-    pub fn default() -> Self {
+impl Default for RapierPhysicsResource {
+    fn default() -> Self {
         Self {
             gravity: vector![0.0, 0.0],
             integration_parameters: IntegrationParameters::default(),
@@ -28,10 +31,13 @@ impl RapierPhysicsResource {
             pipeline: PhysicsPipeline::new(),
         }
     }
+}
+
+impl RapierPhysicsResource {
     pub fn run(mut self) {
         self.pipeline.step(
-            &mut self.gravity, 
-            &mut self.integration_parameters,
+            &self.gravity,
+            &self.integration_parameters,
             &mut self.islands,
             &mut self.broad_phase,
             &mut self.narrow_phase,
@@ -43,4 +49,34 @@ impl RapierPhysicsResource {
             &(),
             &())
     }
-}
\ No newline at end of file
+}
+
+/// Links an entity to the rigid body that is used to simulate it.
+#[derive(Debug, Component)]
+pub struct RigidBodyComponent {
+    pub handle: RigidBodyHandle,
+}
+
+/// Removes the rigid bodies (and any attached colliders) of the entities that have requested a despawn.
+/// This must run before the `DespawnSystem` as the handle is lost once the entity is deleted.
+/// Nothing is done until a `RapierPhysicsResource` has been inserted into the world.
+pub struct RemoveDespawnedBodiesSystem {}
+
+impl <'a> System <'a> for RemoveDespawnedBodiesSystem {
+    type SystemData = (
+        Option<Write<'a, RapierPhysicsResource>>,
+        ReadStorage<'a, RigidBodyComponent>,
+        ReadStorage<'a, DespawnRequest>,
+    );
+    fn run(&mut self, data: Self::SystemData) {
+        let (physics, bodies, despawn_requests) = data;
+        let mut physics = match physics {
+            Some(physics) => physics,
+            None => return,
+        };
+        let RapierPhysicsResource { rigid_bodies, islands, colliders, joints, .. } = &mut *physics;
+        for (body, _) in (&bodies, &despawn_requests).join() {
+            rigid_bodies.remove(body.handle, islands, colliders, joints);
+        }
+    }
+}
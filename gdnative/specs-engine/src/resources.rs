@@ -1,4 +1,17 @@
 //! This contains definitions for specific resources that can be used by the core specs engine.
+mod bounds;
+mod camera_view;
+mod debug_draw;
+mod flow_field;
+mod nav_grid;
+mod spatial_grid;
+pub use bounds::*;
+pub use camera_view::*;
+pub use debug_draw::*;
+pub use flow_field::*;
+pub use nav_grid::*;
+pub use spatial_grid::*;
+
 #[derive(Default, Clone, Copy)]
 pub struct Time {
     pub delta: f32,
@@ -26,4 +39,14 @@ impl <T> WorldMsgQueue <T> {
     pub fn pop(&self) -> Option<T>{
         self.0.pop()
     }
+    /// Pops every message and sorts them by the key. Messages pushed from parallel systems arrive in a different order each run, so
+    /// sorting them first allows them to be applied deterministically.
+    pub fn drain_sorted_by_key<K: Ord, F: FnMut(&T) -> K>(&self, f: F) -> Vec<T> {
+        let mut messages = Vec::with_capacity(self.0.len());
+        while let Some(message) = self.0.pop() {
+            messages.push(message);
+        }
+        messages.sort_by_key(f);
+        messages
+    }
 }
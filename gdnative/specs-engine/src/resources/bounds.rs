@@ -0,0 +1,58 @@
+use std::collections::HashMap;
+
+/// Simple bounding box starting at the coorindate `x`, `y` and extending out `width` and `height`. `width` and `height` must be non-zero.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox { x: f32, y: f32, width: f32, height: f32 }
+impl BoundingBox {
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        assert!(width > 0f32);
+        assert!(height > 0f32);
+        Self { x, y, width, height }
+    }
+    pub fn x(&self) -> f32 {
+        self.x
+    }
+    pub fn y(&self) -> f32 {
+        self.y
+    }
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+    pub fn height(&self) -> f32 {
+        self.height
+    }
+    pub fn x_max(&self) -> f32 {
+        self.x + self.width
+    }
+    pub fn y_max(&self) -> f32 {
+        self.y + self.height
+    }
+    /// Points that lie on the edge of the box are treated as being inside.
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        x >= self.x && x <= self.x_max() && y >= self.y && y <= self.y_max()
+    }
+}
+
+/// A set of named bounding boxes, this allows different entities to be kept inside of different parts of the level.
+/// Entities select a region through their `BoundaryBehavior`, entities that do not name a region use the `BoundingBox` resource instead.
+#[derive(Debug, Default)]
+pub struct BoundingRegions(HashMap<&'static str, BoundingBox>);
+
+impl BoundingRegions {
+    pub fn new() -> Self {
+        Self(HashMap::new())
+    }
+    /// Adds or replaces the region with the given name.
+    pub fn insert(&mut self, name: &'static str, bounding_box: BoundingBox) -> Option<BoundingBox> {
+        self.0.insert(name, bounding_box)
+    }
+    pub fn remove(&mut self, name: &str) -> Option<BoundingBox> {
+        self.0.remove(name)
+    }
+    pub fn get(&self, name: &str) -> Option<&BoundingBox> {
+        self.0.get(name)
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (&&'static str, &BoundingBox)> {
+        self.0.iter()
+    }
+}
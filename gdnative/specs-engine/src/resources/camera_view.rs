@@ -0,0 +1,54 @@
+use crate::resources::BoundingBox;
+
+/// The view of the current `Camera`, written by the `CameraSystem` and applied to the canvas by the renderer.
+/// The screen size must be kept up to date with the size of the viewport.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraView {
+    pub x: f32,
+    pub y: f32,
+    pub zoom: f32,
+    pub rotation: f32,
+    pub screen_width: f32,
+    pub screen_height: f32,
+}
+
+impl CameraView {
+    /// Creates a view with its center in the center of the screen, which matches a canvas without a camera.
+    pub fn new(screen_width: f32, screen_height: f32) -> Self {
+        Self { x: screen_width / 2.0, y: screen_height / 2.0, zoom: 1.0, rotation: 0.0, screen_width, screen_height }
+    }
+    pub fn set_screen_size(&mut self, width: f32, height: f32) {
+        self.screen_width = width;
+        self.screen_height = height;
+    }
+    /// The transform from the world to the screen, as the x axis, y axis and origin in the same order as `Transform2D::new`.
+    pub fn transform(&self) -> [f32; 6] {
+        let (sin, cos) = self.rotation.sin_cos();
+        let (a, b, c, d) = (cos * self.zoom, sin * self.zoom, -sin * self.zoom, cos * self.zoom);
+        [
+            a, b, c, d,
+            self.screen_width / 2.0 - (a * self.x + c * self.y),
+            self.screen_height / 2.0 - (b * self.x + d * self.y),
+        ]
+    }
+    /// Converts a position on the screen, such as the mouse position, to a position in the world.
+    pub fn screen_to_world(&self, x: f32, y: f32) -> (f32, f32) {
+        let (sin, cos) = self.rotation.sin_cos();
+        let zoom = self.zoom.max(f32::EPSILON);
+        let (dx, dy) = (x - self.screen_width / 2.0, y - self.screen_height / 2.0);
+        (self.x + (cos * dx + sin * dy) / zoom, self.y + (cos * dy - sin * dx) / zoom)
+    }
+    /// The area of the world that is visible, this includes the corners that are rotated into view.
+    pub fn visible_rect(&self) -> BoundingBox {
+        let (half_width, half_height) = (self.screen_width / 2.0 / self.zoom, self.screen_height / 2.0 / self.zoom);
+        let (sin, cos) = self.rotation.sin_cos();
+        let extent_x = half_width * cos.abs() + half_height * sin.abs();
+        let extent_y = half_width * sin.abs() + half_height * cos.abs();
+        BoundingBox::new(self.x - extent_x, self.y - extent_y, extent_x * 2.0, extent_y * 2.0)
+    }
+}
+
+/// The area of the world that is visible, used to cull entities that do not need to be drawn.
+/// The `CameraSystem` keeps this up to date with the `CameraView` if both resources exist, otherwise it must be set by the renderer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ViewRect(pub BoundingBox);
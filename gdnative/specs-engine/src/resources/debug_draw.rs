@@ -0,0 +1,61 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use crossbeam::queue::SegQueue;
+
+/// A color as red, green, blue and alpha between 0.0 and 1.0.
+pub type DebugColor = [f32; 4];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DebugShape {
+    Line { from: (f32, f32), to: (f32, f32), width: f32 },
+    Rect { x: f32, y: f32, width: f32, height: f32, filled: bool },
+    Circle { x: f32, y: f32, radius: f32 },
+    Text { x: f32, y: f32, text: String },
+}
+
+/// Shapes to draw over the game for a single frame, such as velocities, bounds, colliders and paths.
+/// Shapes can be pushed from any system (including ones that run in parallel) and are drawn and cleared by the renderer on the main thread
+/// each frame. Nothing is recorded while the debug draw is disabled, so the calls can be left in place.
+#[derive(Debug, Default)]
+pub struct DebugDraw {
+    enabled: AtomicBool,
+    shapes: SegQueue<(DebugShape, DebugColor)>,
+}
+
+impl DebugDraw {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+    pub fn push(&self, shape: DebugShape, color: DebugColor) {
+        if self.is_enabled() {
+            self.shapes.push((shape, color));
+        }
+    }
+    pub fn line(&self, from: (f32, f32), to: (f32, f32), color: DebugColor) {
+        self.push(DebugShape::Line { from, to, width: 1.0 }, color);
+    }
+    /// Draws the outline of the rect.
+    pub fn rect(&self, x: f32, y: f32, width: f32, height: f32, color: DebugColor) {
+        self.push(DebugShape::Rect { x, y, width, height, filled: false }, color);
+    }
+    /// Draws the outline of the circle.
+    pub fn circle(&self, x: f32, y: f32, radius: f32, color: DebugColor) {
+        self.push(DebugShape::Circle { x, y, radius }, color);
+    }
+    pub fn text(&self, x: f32, y: f32, text: impl Into<String>, color: DebugColor) {
+        self.push(DebugShape::Text { x, y, text: text.into() }, color);
+    }
+    /// Removes every shape that has been pushed since the last call.
+    pub fn drain(&self) -> Vec<(DebugShape, DebugColor)> {
+        let mut shapes = Vec::with_capacity(self.shapes.len());
+        while let Some(shape) = self.shapes.pop() {
+            shapes.push(shape);
+        }
+        shapes
+    }
+}
@@ -0,0 +1,188 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use crate::resources::NavGrid;
+
+/// The cost from each cell of the `NavGrid` to the nearest goal. Entities follow the field by moving to the neighbour with the lowest cost,
+/// which lets any number of entities share a single search instead of each one running A*.
+/// The field is only recomputed by `update` when the goals or the grid change. Adding goals and removing walls only needs the affected
+/// cells to be updated, removing goals and adding walls requires the whole field to be recomputed.
+#[derive(Debug, Default)]
+pub struct FlowField {
+    goals: Vec<(f32, f32)>,
+    added_goals: Vec<(f32, f32)>,
+    needs_full_update: bool,
+    // The version and walkable cells of the grid at the last update, used to find which cells have changed.
+    grid_version: Option<u64>,
+    walkable: Vec<bool>,
+    costs: Vec<u32>,
+}
+
+impl FlowField {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn goals(&self) -> &[(f32, f32)] {
+        &self.goals
+    }
+    pub fn add_goal(&mut self, x: f32, y: f32) {
+        self.goals.push((x, y));
+        self.added_goals.push((x, y));
+    }
+    pub fn set_goals(&mut self, goals: Vec<(f32, f32)>) {
+        self.goals = goals;
+        self.added_goals.clear();
+        self.needs_full_update = true;
+    }
+    pub fn clear_goals(&mut self) {
+        self.set_goals(Vec::new());
+    }
+    /// Returns the cost from the cell to the nearest goal, or `None` if no goal can be reached.
+    pub fn cost(&self, grid: &NavGrid, cell: (i32, i32)) -> Option<u32> {
+        grid.index_of(cell)
+            .and_then(|index| self.costs.get(index).copied())
+            .filter(|cost| *cost != u32::MAX)
+    }
+    /// Returns the normalized direction towards the neighbour with the lowest cost.
+    /// Returns `None` if the point is on a goal or no goal can be reached.
+    pub fn direction(&self, grid: &NavGrid, x: f32, y: f32) -> Option<(f32, f32)> {
+        let cell = grid.cell_of(x, y);
+        let mut best = (self.cost(grid, cell)?, cell);
+        grid.for_each_neighbour(cell, |neighbour, _| {
+            if let Some(cost) = self.cost(grid, neighbour) {
+                if cost < best.0 {
+                    best = (cost, neighbour);
+                }
+            }
+        });
+        if best.1 == cell {
+            return None;
+        }
+        let (dx, dy) = ((best.1).0 - cell.0, (best.1).1 - cell.1);
+        let length = ((dx * dx + dy * dy) as f32).sqrt();
+        Some((dx as f32 / length, dy as f32 / length))
+    }
+    /// Brings the field up to date with the goals and the grid. Returns false if nothing needed to change.
+    pub fn update(&mut self, grid: &NavGrid) -> bool {
+        let grid_changed = self.grid_version != Some(grid.version()) || self.walkable.len() != grid.cell_count();
+        if !grid_changed && !self.needs_full_update && self.added_goals.is_empty() {
+            return false;
+        }
+        let mut opened = Vec::new();
+        if grid_changed {
+            if self.walkable.len() != grid.cell_count() {
+                self.needs_full_update = true;
+            } else {
+                for (index, walkable) in self.walkable.iter().enumerate() {
+                    match (*walkable, grid.is_walkable(grid.cell_at(index))) {
+                        (false, true) => opened.push(index),
+                        (true, false) => self.needs_full_update = true,
+                        _ => {},
+                    }
+                }
+            }
+            // A goal that was blocked was never seeded, so it is simpler to start again than to find it.
+            let goal_opened = self.goals.iter().any(|&(x, y)| grid.index_of(grid.cell_of(x, y)).is_some_and(|index| opened.contains(&index)));
+            if goal_opened {
+                self.needs_full_update = true;
+            }
+            self.walkable = (0..grid.cell_count()).map(|index| grid.is_walkable(grid.cell_at(index))).collect();
+            self.grid_version = Some(grid.version());
+        }
+        let mut open = BinaryHeap::new();
+        if self.needs_full_update {
+            self.costs = vec![u32::MAX; grid.cell_count()];
+            for &(x, y) in self.goals.iter() {
+                seed_goal(&mut self.costs, grid, x, y, &mut open);
+            }
+        } else {
+            for (x, y) in std::mem::take(&mut self.added_goals) {
+                seed_goal(&mut self.costs, grid, x, y, &mut open);
+            }
+            // Opening a cell can also allow diagonal moves between its neighbours, so the neighbours are relaxed again as well.
+            for index in opened {
+                let cell = grid.cell_at(index);
+                grid.for_each_neighbour(cell, |neighbour, step| {
+                    let neighbour_index = grid.index_of(neighbour).expect("neighbours are inside of the grid");
+                    let cost = self.costs[neighbour_index];
+                    if cost != u32::MAX {
+                        open.push(Reverse((cost, neighbour_index)));
+                        if cost + step < self.costs[index] {
+                            self.costs[index] = cost + step;
+                        }
+                    }
+                });
+                if self.costs[index] != u32::MAX {
+                    open.push(Reverse((self.costs[index], index)));
+                }
+            }
+        }
+        self.added_goals.clear();
+        self.needs_full_update = false;
+        self.relax(grid, open);
+        true
+    }
+    /// Dijkstra's algorithm starting from the cells that are already in the heap.
+    fn relax(&mut self, grid: &NavGrid, mut open: BinaryHeap<Reverse<(u32, usize)>>) {
+        while let Some(Reverse((cost, index))) = open.pop() {
+            if cost > self.costs[index] {
+                continue;
+            }
+            let costs = &mut self.costs;
+            grid.for_each_neighbour(grid.cell_at(index), |neighbour, step| {
+                let neighbour_index = grid.index_of(neighbour).expect("neighbours are inside of the grid");
+                if cost + step < costs[neighbour_index] {
+                    costs[neighbour_index] = cost + step;
+                    open.push(Reverse((cost + step, neighbour_index)));
+                }
+            });
+        }
+    }
+}
+
+fn seed_goal(costs: &mut [u32], grid: &NavGrid, x: f32, y: f32, open: &mut BinaryHeap<Reverse<(u32, usize)>>) {
+    let cell = grid.cell_of(x, y);
+    match grid.index_of(cell) {
+        Some(index) if grid.is_walkable(cell) => {
+            costs[index] = 0;
+            open.push(Reverse((0, index)));
+        },
+        _ => log::warn!("flow field goal ({}, {}) is not walkable", x, y),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn assert_matches_full_update(field: &FlowField, grid: &NavGrid) {
+        let mut expected = FlowField::new();
+        expected.set_goals(field.goals().to_vec());
+        expected.update(grid);
+        assert_eq!(field.costs, expected.costs);
+    }
+
+    #[test]
+    pub fn test_incremental_update() {
+        let mut grid = NavGrid::new(8, 8, 1.0);
+        for y in 0..7 {
+            grid.set_walkable((4, y), false);
+        }
+        let mut field = FlowField::new();
+        field.add_goal(7.5, 0.5);
+        assert!(field.update(&grid));
+        assert!(!field.update(&grid));
+        // The wall must be passed at the bottom, so the first step from the other side is downwards.
+        assert!(field.direction(&grid, 0.5, 0.5).unwrap().1 > 0.0);
+        assert_eq!(field.direction(&grid, 7.5, 0.5), None);
+
+        grid.set_walkable((4, 0), true);
+        grid.set_walkable((4, 1), true);
+        field.add_goal(0.5, 7.5);
+        assert!(field.update(&grid));
+        assert_matches_full_update(&field, &grid);
+
+        grid.set_walkable((4, 7), false);
+        assert!(field.update(&grid));
+        assert_matches_full_update(&field, &grid);
+    }
+}
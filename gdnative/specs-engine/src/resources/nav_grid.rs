@@ -0,0 +1,218 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use specs::prelude::*;
+use crate::util::WorldQuery;
+
+// Costs are kept as integers so that they can be ordered in the `BinaryHeap`, a diagonal step is roughly 10 * sqrt(2).
+const STRAIGHT_COST: u32 = 10;
+const DIAGONAL_COST: u32 = 14;
+
+// Versions are shared between every grid so that a grid which replaces another never has the same version.
+static NEXT_VERSION: AtomicU64 = AtomicU64::new(0);
+
+fn next_version() -> u64 {
+    NEXT_VERSION.fetch_add(1, Ordering::Relaxed)
+}
+
+/// A grid of walkable and blocked cells used for pathfinding. Cell `(0, 0)` starts at `origin` and each cell is `cell_size` wide.
+/// Any cell outside of the grid is treated as blocked.
+#[derive(Debug, Clone)]
+pub struct NavGrid {
+    origin: (f32, f32),
+    cell_size: f32,
+    width: i32,
+    height: i32,
+    blocked: Vec<bool>,
+    // Changed whenever a cell changes so that anything derived from the grid knows when it must be updated.
+    version: u64,
+}
+
+impl NavGrid {
+    /// Creates a grid where every cell is walkable.
+    pub fn new(width: i32, height: i32, cell_size: f32) -> Self {
+        assert!(width > 0 && height > 0);
+        assert!(cell_size > 0f32);
+        Self { origin: (0.0, 0.0), cell_size, width, height, blocked: vec![false; (width * height) as usize], version: next_version() }
+    }
+    pub fn with_origin(mut self, x: f32, y: f32) -> Self {
+        self.origin = (x, y);
+        self
+    }
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+    pub fn in_bounds(&self, cell: (i32, i32)) -> bool {
+        cell.0 >= 0 && cell.0 < self.width && cell.1 >= 0 && cell.1 < self.height
+    }
+    /// The index of the cell in a `Vec` of `width * height` cells, this is also used by anything that stores a value per cell.
+    pub fn index_of(&self, cell: (i32, i32)) -> Option<usize> {
+        if self.in_bounds(cell) {
+            Some((cell.1 * self.width + cell.0) as usize)
+        } else {
+            None
+        }
+    }
+    pub fn cell_count(&self) -> usize {
+        self.blocked.len()
+    }
+    pub fn cell_at(&self, index: usize) -> (i32, i32) {
+        (index as i32 % self.width, index as i32 / self.width)
+    }
+    /// Returns the cell that contains the point, the cell may be outside of the grid.
+    pub fn cell_of(&self, x: f32, y: f32) -> (i32, i32) {
+        (((x - self.origin.0) / self.cell_size).floor() as i32, ((y - self.origin.1) / self.cell_size).floor() as i32)
+    }
+    pub fn center_of(&self, cell: (i32, i32)) -> (f32, f32) {
+        (
+            self.origin.0 + (cell.0 as f32 + 0.5) * self.cell_size,
+            self.origin.1 + (cell.1 as f32 + 0.5) * self.cell_size,
+        )
+    }
+    pub fn is_walkable(&self, cell: (i32, i32)) -> bool {
+        self.index_of(cell).is_some_and(|index| !self.blocked[index])
+    }
+    pub fn set_walkable(&mut self, cell: (i32, i32), walkable: bool) {
+        if let Some(index) = self.index_of(cell) {
+            if self.blocked[index] == walkable {
+                self.blocked[index] = !walkable;
+                self.version = next_version();
+            }
+        } else {
+            log::warn!("cell {:?} is outside of the nav grid", cell);
+        }
+    }
+    /// Calls `f` with each walkable neighbour of the cell and the cost of moving to it.
+    /// Diagonal moves are only allowed if both of the adjacent cells are walkable so that paths do not cut through corners.
+    pub fn for_each_neighbour<F: FnMut((i32, i32), u32)>(&self, cell: (i32, i32), mut f: F) {
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                if dx == 0 && dy == 0 {
+                    continue;
+                }
+                let neighbour = (cell.0 + dx, cell.1 + dy);
+                if !self.is_walkable(neighbour) {
+                    continue;
+                }
+                if dx != 0 && dy != 0 {
+                    if self.is_walkable((cell.0 + dx, cell.1)) && self.is_walkable((cell.0, cell.1 + dy)) {
+                        f(neighbour, DIAGONAL_COST);
+                    }
+                } else {
+                    f(neighbour, STRAIGHT_COST);
+                }
+            }
+        }
+    }
+    /// Finds the shortest path between two points using A*. The waypoints are the centers of each cell along the path, except for the
+    /// last waypoint which is the goal itself. The start does not need to be walkable so that entities can escape from blocked cells.
+    /// Returns `None` if the goal is blocked or cannot be reached.
+    pub fn find_path(&self, from: (f32, f32), to: (f32, f32)) -> Option<Vec<(f32, f32)>> {
+        let start = self.cell_of(from.0, from.1);
+        let goal = self.cell_of(to.0, to.1);
+        let start_index = self.index_of(start)?;
+        let goal_index = self.index_of(goal)?;
+        if !self.is_walkable(goal) {
+            return None;
+        }
+        // Octile distance, which never overestimates the cost when diagonal moves are allowed.
+        let heuristic = |cell: (i32, i32)| {
+            let (dx, dy) = ((cell.0 - goal.0).unsigned_abs(), (cell.1 - goal.1).unsigned_abs());
+            STRAIGHT_COST * dx.max(dy) + (DIAGONAL_COST - STRAIGHT_COST) * dx.min(dy)
+        };
+        let mut costs = vec![u32::MAX; self.blocked.len()];
+        let mut came_from = vec![usize::MAX; self.blocked.len()];
+        let mut open = BinaryHeap::new();
+        costs[start_index] = 0;
+        open.push(Reverse((heuristic(start), start_index)));
+        while let Some(Reverse((estimate, index))) = open.pop() {
+            if index == goal_index {
+                break;
+            }
+            let cell = self.cell_at(index);
+            let cost = costs[index];
+            // The cell is pushed again whenever a cheaper path to it is found, so entries from before that are stale and skipped.
+            if estimate > cost + heuristic(cell) {
+                continue;
+            }
+            self.for_each_neighbour(cell, |neighbour, step| {
+                let neighbour_index = self.index_of(neighbour).expect("neighbours are inside of the grid");
+                if cost + step < costs[neighbour_index] {
+                    costs[neighbour_index] = cost + step;
+                    came_from[neighbour_index] = index;
+                    open.push(Reverse((cost + step + heuristic(neighbour), neighbour_index)));
+                }
+            });
+        }
+        if costs[goal_index] == u32::MAX {
+            return None;
+        }
+        let mut waypoints = vec![to];
+        let mut index = came_from[goal_index];
+        while index != usize::MAX && index != start_index {
+            waypoints.push(self.center_of(self.cell_at(index)));
+            index = came_from[index];
+        }
+        waypoints.reverse();
+        Some(waypoints)
+    }
+}
+
+/// Finds a path between two points on the `NavGrid`. `Args` are `(from, to)`.
+/// Returns `None` if there is no `NavGrid` or no path.
+pub struct FindPath {}
+
+impl WorldQuery for FindPath {
+    type Args = ((f32, f32), (f32, f32));
+    type Output = Option<Vec<(f32, f32)>>;
+    fn query(world: &World, args: Self::Args) -> Self::Output {
+        let (from, to) = args;
+        world.try_fetch::<NavGrid>().and_then(|grid| grid.find_path(from, to))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_find_path_around_wall() {
+        let mut grid = NavGrid::new(5, 5, 10.0);
+        // A wall down the middle with a gap at the bottom.
+        for y in 0..4 {
+            grid.set_walkable((2, y), false);
+        }
+        let path = grid.find_path((5.0, 5.0), (45.0, 5.0)).expect("there should be a path through the gap");
+        assert_eq!(*path.last().unwrap(), (45.0, 5.0));
+        assert!(path.iter().any(|&(x, y)| grid.cell_of(x, y) == (2, 4)));
+        assert!(path.iter().all(|&(x, y)| grid.is_walkable(grid.cell_of(x, y))));
+
+        grid.set_walkable((2, 4), false);
+        assert!(grid.find_path((5.0, 5.0), (45.0, 5.0)).is_none());
+    }
+
+    #[test]
+    pub fn test_replaced_grid_updates_flow_field() {
+        let mut grid = NavGrid::new(4, 1, 10.0);
+        grid.set_walkable((3, 0), false);
+        let mut field = crate::resources::FlowField::new();
+        field.add_goal(25.0, 5.0);
+        field.update(&grid);
+        assert_eq!(field.cost(&grid, (0, 0)), Some(20));
+
+        // The replacement has the same size and number of edits, but must still be seen as a change.
+        grid = NavGrid::new(4, 1, 10.0);
+        grid.set_walkable((1, 0), false);
+        assert!(field.update(&grid));
+        assert_eq!(field.cost(&grid, (0, 0)), None);
+    }
+}
@@ -0,0 +1,151 @@
+use specs::prelude::*;
+use std::collections::HashMap;
+use crate::util::WorldQuery;
+
+/// An entity and the position it had when the grid was last updated.
+#[derive(Debug, Clone, Copy)]
+pub struct GridEntry {
+    pub entity: Entity,
+    pub x: f32,
+    pub y: f32,
+}
+
+/// A spatial hash grid that buckets entities by their `Position` so that neighbours can be found without checking every entity.
+/// The grid is rebuilt by the `UpdateSpatialGridSystem`, so positions are only as recent as the last time that the system was run.
+/// `cell_size` should be roughly the size of the most common query radius.
+#[derive(Debug)]
+pub struct SpatialGrid {
+    cell_size: f32,
+    cells: HashMap<(i32, i32), Vec<GridEntry>>,
+}
+
+impl SpatialGrid {
+    pub fn new(cell_size: f32) -> Self {
+        assert!(cell_size > 0f32);
+        Self { cell_size, cells: HashMap::new() }
+    }
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+    pub fn cell_of(&self, x: f32, y: f32) -> (i32, i32) {
+        ((x / self.cell_size).floor() as i32, (y / self.cell_size).floor() as i32)
+    }
+    /// Empties every cell. Cells that were occupied keep their allocations so that they can be reused by the next update,
+    /// while cells that were already empty are dropped so that entities moving across the world do not leave a trail of cells behind.
+    pub fn clear(&mut self) {
+        self.cells.retain(|_, cell| {
+            let occupied = !cell.is_empty();
+            cell.clear();
+            occupied
+        });
+    }
+    pub fn insert(&mut self, entity: Entity, x: f32, y: f32) {
+        let cell = self.cell_of(x, y);
+        self.cells.entry(cell).or_default().push(GridEntry { entity, x, y });
+    }
+    /// Inserts entries whose cells have already been calculated, eg. by a `par_join`.
+    pub fn insert_all<I: IntoIterator<Item = ((i32, i32), GridEntry)>>(&mut self, entries: I) {
+        for (cell, entry) in entries {
+            self.cells.entry(cell).or_default().push(entry);
+        }
+    }
+    /// Calls `f` for every entry within the rectangle starting at `x`, `y` and extending out `width` and `height`.
+    pub fn for_each_in_rect<F: FnMut(&GridEntry)>(&self, x: f32, y: f32, width: f32, height: f32, f: F) {
+        self.for_each_in_bounds((x, y), (x + width, y + height), f);
+    }
+    /// Calls `f` for every entry between the `min` and `max` corners.
+    /// If the bounds cover more cells than are occupied, eg. for a huge or infinite radius, the occupied cells are visited instead.
+    fn for_each_in_bounds<F: FnMut(&GridEntry)>(&self, min: (f32, f32), max: (f32, f32), mut f: F) {
+        let (min_x, min_y) = self.cell_of(min.0, min.1);
+        let (max_x, max_y) = self.cell_of(max.0, max.1);
+        let mut visit = |cell: &Vec<GridEntry>| {
+            for entry in cell.iter() {
+                if entry.x >= min.0 && entry.x <= max.0 && entry.y >= min.1 && entry.y <= max.1 {
+                    f(entry);
+                }
+            }
+        };
+        let columns = (max_x as i64 - min_x as i64 + 1).max(0) as u64;
+        let rows = (max_y as i64 - min_y as i64 + 1).max(0) as u64;
+        if columns.saturating_mul(rows) > self.cells.len() as u64 {
+            for (&(cell_x, cell_y), cell) in self.cells.iter() {
+                if cell_x >= min_x && cell_x <= max_x && cell_y >= min_y && cell_y <= max_y {
+                    visit(cell);
+                }
+            }
+            return;
+        }
+        for cell_x in min_x..=max_x {
+            for cell_y in min_y..=max_y {
+                if let Some(cell) = self.cells.get(&(cell_x, cell_y)) {
+                    visit(cell);
+                }
+            }
+        }
+    }
+    /// Calls `f` for every entry within `radius` of `x`, `y`.
+    /// This does not allocate, so it is preferred over `query_radius` inside of systems.
+    pub fn for_each_in_radius<F: FnMut(&GridEntry)>(&self, x: f32, y: f32, radius: f32, mut f: F) {
+        let radius_squared = radius * radius;
+        self.for_each_in_bounds((x - radius, y - radius), (x + radius, y + radius), |entry| {
+            let dx = entry.x - x;
+            let dy = entry.y - y;
+            if dx * dx + dy * dy <= radius_squared {
+                f(entry);
+            }
+        });
+    }
+    pub fn query_radius(&self, x: f32, y: f32, radius: f32) -> Vec<Entity> {
+        let mut found = Vec::new();
+        self.for_each_in_radius(x, y, radius, |entry| found.push(entry.entity));
+        found
+    }
+    pub fn query_rect(&self, x: f32, y: f32, width: f32, height: f32) -> Vec<Entity> {
+        let mut found = Vec::new();
+        self.for_each_in_rect(x, y, width, height, |entry| found.push(entry.entity));
+        found
+    }
+}
+
+/// Finds all of the entities within a radius of a point. `Args` are `(x, y, radius)`.
+pub struct EntitiesInRadius {}
+
+impl WorldQuery for EntitiesInRadius {
+    type Args = (f32, f32, f32);
+    type Output = Vec<Entity>;
+    fn query(world: &World, args: Self::Args) -> Self::Output {
+        let (x, y, radius) = args;
+        world.try_fetch::<SpatialGrid>().map(|grid| grid.query_radius(x, y, radius)).unwrap_or_default()
+    }
+}
+
+/// Finds all of the entities within a rectangle. `Args` are `(x, y, width, height)`.
+pub struct EntitiesInRect {}
+
+impl WorldQuery for EntitiesInRect {
+    type Args = (f32, f32, f32, f32);
+    type Output = Vec<Entity>;
+    fn query(world: &World, args: Self::Args) -> Self::Output {
+        let (x, y, width, height) = args;
+        world.try_fetch::<SpatialGrid>().map(|grid| grid.query_rect(x, y, width, height)).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_clear_drops_empty_cells() {
+        let mut world = World::new();
+        let entity = world.create_entity().build();
+        let mut grid = SpatialGrid::new(1.0);
+        for x in 0..100 {
+            grid.clear();
+            grid.insert(entity, x as f32, 0.0);
+        }
+        // Only the cell occupied before the last clear is kept alongside the current one.
+        assert_eq!(grid.cells.len(), 2);
+        assert_eq!(grid.query_radius(99.0, 0.0, 0.5), vec![entity]);
+    }
+}
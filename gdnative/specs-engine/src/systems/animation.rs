@@ -0,0 +1,78 @@
+use specs::prelude::*;
+use crate::components::*;
+use crate::resources::*;
+
+/// Sent when a `SpriteAnimation` that does not loop reaches the last frame of its clip.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnimationFinished(pub Entity, pub &'static str);
+
+/// Advances every `SpriteAnimation` and sets the frame of its `SpriteSheet`. The sheet is only modified when the frame changes.
+// Note: The `Time` and `WorldMsgQueue<AnimationFinished>` resources MUST be added to the simulation for this system to work.
+pub struct SpriteAnimationSystem {}
+
+impl <'a> System <'a> for SpriteAnimationSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Time>,
+        ReadExpect<'a, WorldMsgQueue<AnimationFinished>>,
+        WriteStorage<'a, SpriteAnimation>,
+        WriteStorage<'a, SpriteSheet>,
+    );
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, time, finished_queue, mut animations, mut sheets) = data;
+        for (entity, animation) in (&entities, &mut animations).join() {
+            if animation.finished || animation.fps <= 0.0 {
+                continue;
+            }
+            let len = animation.clips.get(animation.current).map_or(0, |frames| frames.len());
+            if len == 0 {
+                continue;
+            }
+            let seconds_per_frame = 1.0 / animation.fps;
+            animation.elapsed += time.delta;
+            while animation.elapsed >= seconds_per_frame {
+                animation.elapsed -= seconds_per_frame;
+                if animation.index + 1 < len {
+                    animation.index += 1;
+                } else if animation.looping {
+                    animation.index = 0;
+                } else {
+                    animation.finished = true;
+                    animation.elapsed = 0.0;
+                    finished_queue.push(AnimationFinished(entity, animation.current));
+                    break;
+                }
+            }
+            // Only modify the sheet if the frame has changed so that its change events can be used to skip unchanged entities.
+            if let Some(frame) = animation.frame() {
+                if sheets.get(entity).is_some_and(|sheet| sheet.frame != frame) {
+                    sheets.get_mut(entity).expect("the sheet was just read").frame = frame;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_sprite_animation() {
+        let mut world = World::new();
+        crate::register_components(&mut world);
+        world.insert(Time { delta: 0.35, total: 0.0 });
+        world.insert(WorldMsgQueue::<AnimationFinished>::new());
+        let animation = SpriteAnimation::new(10.0, false).with_clip("attack", vec![4, 5, 6]);
+        let entity = world.create_entity().with(SpriteSheet::new(4, 2)).with(animation).build();
+        SpriteAnimationSystem {}.run_now(&world);
+
+        // 3.5 frames have passed but the clip only has 3 frames, so it finishes on the last one.
+        let sheets = world.read_storage::<SpriteSheet>();
+        let sheet = sheets.get(entity).unwrap();
+        assert_eq!(sheet.frame, 6);
+        assert_eq!(sheet.region(64.0, 32.0), (32.0, 16.0, 16.0, 16.0));
+        assert!(world.read_storage::<SpriteAnimation>().get(entity).unwrap().is_finished());
+        assert_eq!(world.read_resource::<WorldMsgQueue<AnimationFinished>>().pop(), Some(AnimationFinished(entity, "attack")));
+    }
+}
@@ -0,0 +1,77 @@
+//! This module contains the system used to tick behaviour trees.
+use specs::prelude::*;
+use crate::commands::DeferredCommand;
+use crate::components::*;
+use crate::resources::*;
+
+/// Ticks every `BehaviorTree` in parallel. Leaves need read access to the whole world, so this is not a `System` and must either be
+/// added with `DispatcherBuilder::with_thread_local` or run with `run_now`. Trees whose root finishes start again from the root on the next tick.
+// Note: Both the `Time` and `WorldMsgQueue<DeferredCommand>` resources MUST be added to the simulation for this system to work.
+pub struct BehaviorTreeSystem {}
+
+impl <'a> RunNow<'a> for BehaviorTreeSystem {
+    fn run_now(&mut self, world: &'a World) {
+        let delta = world.fetch::<Time>().delta;
+        let commands = world.fetch::<WorldMsgQueue<DeferredCommand>>();
+        let entities = world.entities();
+        let mut trees = world.write_storage::<BehaviorTree>();
+        (&entities, &mut trees).par_join().for_each(|(entity, tree)| {
+            tree.tick(entity, world, delta, &commands);
+        });
+    }
+    fn setup(&mut self, world: &mut World) {
+        world.register::<BehaviorTree>();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::behavior::*;
+    use crate::commands::{ApplyDeferredCommands, DespawnEntity};
+    use crate::util::WorldCommand;
+
+    #[test]
+    pub fn test_behavior_tree() {
+        let mut world = World::new();
+        crate::register_components(&mut world);
+        world.insert(Time { delta: 0.5, total: 0.0 });
+        world.insert(WorldMsgQueue::<DeferredCommand>::new());
+        let mut registry = BehaviorRegistry::new();
+        registry.register_leaf("is_far", |context| {
+            let positions = context.world.read_storage::<Position>();
+            match positions.get(context.entity) {
+                Some(position) if position.x > 10.0 => Status::Success,
+                _ => Status::Failure,
+            }
+        });
+        registry.register_leaf("wait", |context| {
+            let waited = context.blackboard.get_float("waited").unwrap_or(0.0) + context.delta;
+            context.blackboard.set("waited", BlackboardValue::Float(waited));
+            if waited >= 1.0 { Status::Success } else { Status::Running }
+        });
+        registry.register_command::<DespawnEntity>("despawn");
+        // Entities that are far away wait a second and are then despawned, anything closer is left alone.
+        let definition = BehaviorNodeDef::Selector(vec![
+            BehaviorNodeDef::Inverter(Box::new(BehaviorNodeDef::Leaf("is_far".to_owned()))),
+            BehaviorNodeDef::Sequence(vec![
+                BehaviorNodeDef::Leaf("wait".to_owned()),
+                BehaviorNodeDef::Leaf("despawn".to_owned()),
+            ]),
+        ]);
+        assert!(registry.build(&BehaviorNodeDef::Leaf("missing".to_owned())).is_err());
+        let template = registry.build(&definition).expect("the tree should build");
+        let near = world.create_entity().with(Position { x: 0.0, y: 0.0 }).with(BehaviorTree::new(template.clone())).build();
+        let far = world.create_entity().with(Position { x: 20.0, y: 0.0 }).with(BehaviorTree::new(template)).build();
+
+        let mut system = BehaviorTreeSystem {};
+        system.run_now(&world);
+        assert_eq!(world.read_storage::<BehaviorTree>().get(near).unwrap().status(), Some(Status::Success));
+        assert_eq!(world.read_storage::<BehaviorTree>().get(far).unwrap().status(), Some(Status::Running));
+        system.run_now(&world);
+        assert_eq!(world.read_storage::<BehaviorTree>().get(far).unwrap().status(), Some(Status::Success));
+        assert_eq!(ApplyDeferredCommands::execute(&mut world, ()), 1);
+        assert!(world.read_storage::<DespawnRequest>().contains(far));
+        assert!(!world.read_storage::<DespawnRequest>().contains(near));
+    }
+}
@@ -0,0 +1,176 @@
+//! This module contains the system used to keep entities inside of their bounding regions.
+use specs::prelude::*;
+use crate::components::*;
+use crate::resources::*;
+
+/// This event is emitted when an entity with the `Despawn` or `Event` `BoundaryMode` leaves its region. The second value is the region name,
+/// or `None` for the `BoundingBox` resource.
+#[derive(Debug, Clone, Copy)]
+pub struct BoundaryCrossed(pub Entity, pub Option<&'static str>);
+
+/// Wraps `value` into the range `min..=min + size`.
+fn wrap(value: f32, min: f32, size: f32) -> f32 {
+    min + (value - min).rem_euclid(size)
+}
+
+/// Reflects `value` back inside of `min..=max` and returns true if it was outside.
+fn reflect(value: &mut f32, min: f32, max: f32) -> bool {
+    if *value < min {
+        *value = (2.0 * min - *value).min(max);
+        true
+    } else if *value > max {
+        *value = (2.0 * max - *value).max(min);
+        true
+    } else {
+        false
+    }
+}
+
+/// Applies the `BoundaryBehavior` of each entity after it has moved. This should run after the positions have been updated.
+/// Entities whose region does not exist are left alone.
+// Note: The `WorldMsgQueue<BoundaryCrossed>` resource MUST be added to the simulation for this system to work.
+pub struct BoundaryBehaviorSystem {}
+
+impl <'a> System <'a> for BoundaryBehaviorSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        Option<Read<'a, BoundingBox>>,
+        Option<Read<'a, BoundingRegions>>,
+        ReadExpect<'a, WorldMsgQueue<BoundaryCrossed>>,
+        WriteStorage<'a, BoundaryBehavior>,
+        WriteStorage<'a, Position>,
+        WriteStorage<'a, Velocity>,
+        WriteStorage<'a, DespawnRequest>,
+    );
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, bounding_box, regions, events, mut behaviors, mut positions, mut velocities, mut despawn_requests) = data;
+        let bounding_box = bounding_box.as_deref();
+        let regions = regions.as_deref();
+        let despawned: Vec<Entity> = (&entities, &mut behaviors, &mut positions, (&mut velocities).maybe(), !&despawn_requests)
+            .par_join()
+            .filter_map(|(entity, behavior, position, velocity, _)| {
+                let bounds = match behavior.region {
+                    Some(name) => regions.and_then(|regions| regions.get(name)),
+                    None => bounding_box,
+                }?;
+                let outside = !bounds.contains(position.x, position.y);
+                let crossed = outside && !behavior.outside;
+                behavior.outside = outside;
+                if !outside {
+                    return None;
+                }
+                match behavior.mode {
+                    BoundaryMode::Bounce { restitution } => {
+                        let bounced_x = reflect(&mut position.x, bounds.x(), bounds.x_max());
+                        let bounced_y = reflect(&mut position.y, bounds.y(), bounds.y_max());
+                        if let Some(velocity) = velocity {
+                            if bounced_x {
+                                velocity.x = -velocity.x * restitution;
+                            }
+                            if bounced_y {
+                                velocity.y = -velocity.y * restitution;
+                            }
+                        }
+                        behavior.outside = false;
+                        None
+                    },
+                    BoundaryMode::Wrap => {
+                        position.x = wrap(position.x, bounds.x(), bounds.width());
+                        position.y = wrap(position.y, bounds.y(), bounds.height());
+                        behavior.outside = false;
+                        None
+                    },
+                    BoundaryMode::Clamp => {
+                        let (x, y) = (position.x.clamp(bounds.x(), bounds.x_max()), position.y.clamp(bounds.y(), bounds.y_max()));
+                        if let Some(velocity) = velocity {
+                            if x != position.x {
+                                velocity.x = 0.0;
+                            }
+                            if y != position.y {
+                                velocity.y = 0.0;
+                            }
+                        }
+                        position.x = x;
+                        position.y = y;
+                        behavior.outside = false;
+                        None
+                    },
+                    BoundaryMode::Despawn => {
+                        events.push(BoundaryCrossed(entity, behavior.region));
+                        Some(entity)
+                    },
+                    BoundaryMode::Event => {
+                        if crossed {
+                            events.push(BoundaryCrossed(entity, behavior.region));
+                        }
+                        None
+                    },
+                }
+            })
+            .collect();
+        for entity in despawned {
+            despawn_requests.insert(entity, DespawnRequest).expect("the entity should be alive");
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn create_world() -> World {
+        let mut world = World::new();
+        crate::register_components(&mut world);
+        world.insert(BoundingBox::new(0.0, 0.0, 100.0, 100.0));
+        let mut regions = BoundingRegions::new();
+        regions.insert("arena", BoundingBox::new(0.0, 0.0, 10.0, 10.0));
+        world.insert(regions);
+        world.insert(WorldMsgQueue::<BoundaryCrossed>::new());
+        world
+    }
+
+    fn create_entity(world: &mut World, x: f32, y: f32, behavior: BoundaryBehavior) -> Entity {
+        world.create_entity()
+            .with(Position { x, y })
+            .with(Velocity { x: 10.0, y: -10.0 })
+            .with(behavior)
+            .build()
+    }
+
+    #[test]
+    pub fn test_boundary_modes() {
+        let mut world = create_world();
+        let bounce = create_entity(&mut world, 105.0, 50.0, BoundaryBehavior::new(BoundaryMode::Bounce { restitution: 0.5 }));
+        let wrap = create_entity(&mut world, 105.0, -5.0, BoundaryBehavior::new(BoundaryMode::Wrap));
+        let clamp = create_entity(&mut world, 12.0, 5.0, BoundaryBehavior::new(BoundaryMode::Clamp).in_region("arena"));
+        let despawn = create_entity(&mut world, 50.0, 50.0, BoundaryBehavior::new(BoundaryMode::Despawn).in_region("arena"));
+        BoundaryBehaviorSystem {}.run_now(&world);
+
+        let positions = world.read_storage::<Position>();
+        let velocities = world.read_storage::<Velocity>();
+        assert_eq!(positions.get(bounce).unwrap().x, 95.0);
+        assert_eq!(velocities.get(bounce).unwrap().x, -5.0);
+        assert_eq!(positions.get(wrap).unwrap().x, 5.0);
+        assert_eq!(positions.get(wrap).unwrap().y, 95.0);
+        assert_eq!(positions.get(clamp).unwrap().x, 10.0);
+        assert_eq!(velocities.get(clamp).unwrap().x, 0.0);
+        assert!(world.read_storage::<DespawnRequest>().contains(despawn));
+        let events = world.fetch::<WorldMsgQueue<BoundaryCrossed>>();
+        let event = events.pop().expect("the despawned entity should emit an event");
+        assert_eq!(event.0, despawn);
+        assert_eq!(event.1, Some("arena"));
+        assert!(events.pop().is_none());
+    }
+
+    #[test]
+    pub fn test_event_only_emitted_once() {
+        let mut world = create_world();
+        let entity = create_entity(&mut world, 150.0, 50.0, BoundaryBehavior::new(BoundaryMode::Event));
+        BoundaryBehaviorSystem {}.run_now(&world);
+        BoundaryBehaviorSystem {}.run_now(&world);
+        let events = world.fetch::<WorldMsgQueue<BoundaryCrossed>>();
+        assert_eq!(events.pop().map(|event| event.0), Some(entity));
+        assert!(events.pop().is_none());
+    }
+}
@@ -0,0 +1,115 @@
+use specs::prelude::*;
+use crate::components::*;
+use crate::resources::*;
+
+/// Moves every `Camera` towards its target and writes the view of the first `current` camera into the `CameraView`.
+/// The `ViewRect` is also updated to the visible area of the view if it has been added.
+/// This should run after anything that moves the camera targets. Nothing is done until a `CameraView` has been added.
+// Note: The `Time` resource MUST be added to the simulation for this system to work.
+pub struct CameraSystem {}
+
+impl <'a> System <'a> for CameraSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Time>,
+        Option<Write<'a, CameraView>>,
+        Option<Write<'a, ViewRect>>,
+        ReadStorage<'a, Position>,
+        WriteStorage<'a, Camera>,
+        WriteStorage<'a, CameraShake>,
+    );
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, time, view, view_rect, positions, mut cameras, mut shakes) = data;
+        let mut view = match view {
+            Some(view) => view,
+            None => return,
+        };
+        let mut current = None;
+        for (entity, camera) in (&entities, &mut cameras).join() {
+            if let Some(target) = camera.target.and_then(|target| positions.get(target)) {
+                let goal_x = follow(camera.x, target.x, camera.dead_zone.0);
+                let goal_y = follow(camera.y, target.y, camera.dead_zone.1);
+                // Smoothing is applied exponentially so that the camera moves the same way regardless of the frame rate.
+                let weight = camera.smoothing.map_or(1.0, |smoothing| 1.0 - (1.0 - smoothing.clamp(0.0, 1.0)).powf(time.delta * 60.0));
+                camera.x += (goal_x - camera.x) * weight;
+                camera.y += (goal_y - camera.y) * weight;
+            }
+            if let Some(limits) = camera.limits {
+                let zoom = camera.zoom.max(f32::EPSILON);
+                camera.x = limit(camera.x, view.screen_width / 2.0 / zoom, limits.x(), limits.x_max());
+                camera.y = limit(camera.y, view.screen_height / 2.0 / zoom, limits.y(), limits.y_max());
+            }
+            if camera.current && current.is_none() {
+                current = Some(entity);
+            }
+        }
+        for shake in (&mut shakes).join() {
+            shake.time += time.delta;
+            shake.trauma = (shake.trauma - shake.decay * time.delta).max(0.0);
+        }
+        if let Some(entity) = current {
+            let camera = cameras.get(entity).expect("the camera was just joined");
+            let (offset_x, offset_y, rotation) = shakes.get(entity).map_or((0.0, 0.0, 0.0), |shake| shake.shake());
+            view.x = camera.x + offset_x;
+            view.y = camera.y + offset_y;
+            view.zoom = camera.zoom;
+            view.rotation = rotation;
+        }
+        if let Some(mut view_rect) = view_rect {
+            view_rect.0 = view.visible_rect();
+        }
+    }
+}
+
+/// Returns the closest position to the camera that keeps the target inside of the dead zone.
+fn follow(camera: f32, target: f32, dead_zone: f32) -> f32 {
+    if target > camera + dead_zone {
+        target - dead_zone
+    } else if target < camera - dead_zone {
+        target + dead_zone
+    } else {
+        camera
+    }
+}
+
+/// Keeps the view inside of the limits, if the limits are smaller than the view then the view is centered on them.
+fn limit(center: f32, half_extent: f32, min: f32, max: f32) -> f32 {
+    if max - min < half_extent * 2.0 {
+        (min + max) / 2.0
+    } else {
+        center.clamp(min + half_extent, max - half_extent)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_camera_follow() {
+        let mut world = World::new();
+        crate::register_components(&mut world);
+        world.insert(Time { delta: 1.0 / 60.0, total: 0.0 });
+        world.insert(CameraView::new(100.0, 100.0));
+        let target = world.create_entity().with(Position { x: 120.0, y: 50.0 }).build();
+        let camera = Camera::new(50.0, 50.0)
+            .following(target)
+            .with_dead_zone(10.0, 10.0)
+            .with_limits(BoundingBox::new(0.0, 0.0, 200.0, 80.0));
+        world.create_entity().with(camera).build();
+        CameraSystem {}.run_now(&world);
+
+        // The target is kept at the edge of the dead zone and the view cannot go below the limits.
+        let view = *world.read_resource::<CameraView>();
+        assert_eq!((view.x, view.y), (110.0, 40.0));
+        assert_eq!(view.transform(), [1.0, 0.0, -0.0, 1.0, -60.0, 10.0]);
+        let rect = view.visible_rect();
+        assert_eq!((rect.x(), rect.y(), rect.y_max()), (60.0, -10.0, 90.0));
+        // The top left of the screen is the top left of the visible area.
+        assert_eq!(view.screen_to_world(0.0, 0.0), (60.0, -10.0));
+        let rotated = CameraView { rotation: 0.5, zoom: 2.0, ..view };
+        let [a, b, c, d, origin_x, origin_y] = rotated.transform();
+        let (x, y) = rotated.screen_to_world(a * 75.0 + c * 20.0 + origin_x, b * 75.0 + d * 20.0 + origin_y);
+        assert!((x - 75.0).abs() < 0.001 && (y - 20.0).abs() < 0.001);
+    }
+}
@@ -0,0 +1,229 @@
+//! This module contains a lightweight overlap check for entities that only need trigger style collisions.
+//! No forces are applied, for a full simulation use the Rapier integration instead.
+use specs::prelude::*;
+use std::collections::HashSet;
+use crate::components::*;
+use crate::resources::*;
+
+/// Emitted on the first frame that two colliders overlap. The entity with the lower id is always first.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OverlapStarted(pub Entity, pub Entity);
+
+/// Emitted on the first frame that two colliders no longer overlap, including when either entity is deleted or loses its collider.
+/// The entity with the lower id is always first.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OverlapEnded(pub Entity, pub Entity);
+
+#[derive(Debug, Clone, Copy)]
+enum Shape {
+    Circle(f32),
+    Aabb(f32, f32),
+}
+
+impl Shape {
+    /// The half extents of the shape's bounding box.
+    fn half_extents(&self) -> (f32, f32) {
+        match *self {
+            Shape::Circle(radius) => (radius, radius),
+            Shape::Aabb(half_width, half_height) => (half_width, half_height),
+        }
+    }
+}
+
+fn shape_of(circle: Option<&CircleCollider>, aabb: Option<&AabbCollider>) -> Option<Shape> {
+    match (circle, aabb) {
+        (Some(circle), _) => Some(Shape::Circle(circle.radius)),
+        (None, Some(aabb)) => Some(Shape::Aabb(aabb.half_width, aabb.half_height)),
+        (None, None) => None,
+    }
+}
+
+fn circle_overlaps_aabb(cx: f32, cy: f32, radius: f32, bx: f32, by: f32, half_width: f32, half_height: f32) -> bool {
+    let dx = cx - cx.clamp(bx - half_width, bx + half_width);
+    let dy = cy - cy.clamp(by - half_height, by + half_height);
+    dx * dx + dy * dy <= radius * radius
+}
+
+fn overlaps(a: &Position, a_shape: Shape, b: &Position, b_shape: Shape) -> bool {
+    match (a_shape, b_shape) {
+        (Shape::Circle(a_radius), Shape::Circle(b_radius)) => {
+            let (dx, dy) = (a.x - b.x, a.y - b.y);
+            let radius = a_radius + b_radius;
+            dx * dx + dy * dy <= radius * radius
+        },
+        (Shape::Aabb(a_width, a_height), Shape::Aabb(b_width, b_height)) => {
+            (a.x - b.x).abs() <= a_width + b_width && (a.y - b.y).abs() <= a_height + b_height
+        },
+        (Shape::Circle(radius), Shape::Aabb(half_width, half_height)) => circle_overlaps_aabb(a.x, a.y, radius, b.x, b.y, half_width, half_height),
+        (Shape::Aabb(half_width, half_height), Shape::Circle(radius)) => circle_overlaps_aabb(b.x, b.y, radius, a.x, a.y, half_width, half_height),
+    }
+}
+
+/// Finds every overlapping pair of `CircleCollider`s and `AabbCollider`s and emits events when pairs start or stop overlapping.
+/// If an entity has both colliders, only the circle is used. The `SpatialGrid` is used as the broad phase, so it should be updated before this runs.
+/// Colliders that are larger than a cell of the grid are checked against every collider instead, so that they do not widen the search of the others.
+// Note: The `SpatialGrid`, `WorldMsgQueue<OverlapStarted>` and `WorldMsgQueue<OverlapEnded>` resources MUST be added to the simulation for this system to work.
+#[derive(Default)]
+pub struct OverlapSystem {
+    overlapping: HashSet<(Entity, Entity)>,
+}
+
+impl OverlapSystem {
+    /// Returns true if the pair was overlapping the last time that the system ran. The order of the entities does not matter.
+    pub fn is_overlapping(&self, a: Entity, b: Entity) -> bool {
+        let pair = if a.id() < b.id() { (a, b) } else { (b, a) };
+        self.overlapping.contains(&pair)
+    }
+}
+
+impl <'a> System <'a> for OverlapSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, SpatialGrid>,
+        ReadExpect<'a, WorldMsgQueue<OverlapStarted>>,
+        ReadExpect<'a, WorldMsgQueue<OverlapEnded>>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, CircleCollider>,
+        ReadStorage<'a, AabbCollider>,
+        ReadStorage<'a, CollisionLayers>,
+    );
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, grid, started, ended, positions, circles, aabbs, layers) = data;
+        let default_layers = CollisionLayers::default();
+        let large_extent = grid.cell_size();
+        let is_large = |shape: &Shape| {
+            let (half_width, half_height) = shape.half_extents();
+            half_width > large_extent || half_height > large_extent
+        };
+        let colliders = || (&entities, &positions, circles.maybe(), aabbs.maybe()).join()
+            .filter_map(|(entity, position, circle, aabb)| shape_of(circle, aabb).map(|shape| (entity, position, shape)));
+        let large: Vec<(Entity, &Position, Shape)> = colliders().filter(|(_, _, shape)| is_large(shape)).collect();
+        // Any pair closer than the two largest extents could overlap, so the broad phase must search at least that far.
+        // The large colliders are left out, which keeps the search within a cell or so of each entity.
+        let (max_width, max_height) = colliders().filter(|(_, _, shape)| !is_large(shape)).fold((0.0f32, 0.0f32), |max, (_, _, shape)| {
+            let (half_width, half_height) = shape.half_extents();
+            (max.0.max(half_width), max.1.max(half_height))
+        });
+        let current: HashSet<(Entity, Entity)> = (&entities, &positions, circles.maybe(), aabbs.maybe())
+            .par_join()
+            .filter_map(|(entity, position, circle, aabb)| shape_of(circle, aabb).map(|shape| (entity, position, shape)))
+            .flat_map_iter(|(entity, position, shape)| {
+                let entity_layers = layers.get(entity).unwrap_or(&default_layers);
+                let mut pairs = Vec::new();
+                let mut check = |other: Entity, other_position: &Position, other_shape: Shape| {
+                    // Each pair is only checked by the entity with the lower id.
+                    if other.id() <= entity.id() {
+                        return;
+                    }
+                    let other_layers = layers.get(other).unwrap_or(&default_layers);
+                    if !entity_layers.detects(other_layers) && !other_layers.detects(entity_layers) {
+                        return;
+                    }
+                    if overlaps(position, shape, other_position, other_shape) {
+                        pairs.push((entity, other));
+                    }
+                };
+                let (half_width, half_height) = shape.half_extents();
+                let (search_width, search_height) = (half_width + max_width, half_height + max_height);
+                grid.for_each_in_rect(position.x - search_width, position.y - search_height, search_width * 2.0, search_height * 2.0, |entry| {
+                    let other_shape = match shape_of(circles.get(entry.entity), aabbs.get(entry.entity)) {
+                        Some(shape) if !is_large(&shape) => shape,
+                        _ => return,
+                    };
+                    if let Some(other_position) = positions.get(entry.entity) {
+                        check(entry.entity, other_position, other_shape);
+                    }
+                });
+                for &(other, other_position, other_shape) in large.iter() {
+                    check(other, other_position, other_shape);
+                }
+                pairs
+            })
+            .collect();
+        for pair in current.difference(&self.overlapping) {
+            started.push(OverlapStarted(pair.0, pair.1));
+        }
+        for pair in self.overlapping.difference(&current) {
+            ended.push(OverlapEnded(pair.0, pair.1));
+        }
+        self.overlapping = current;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::systems::UpdateSpatialGridSystem;
+
+    fn create_world() -> World {
+        let mut world = World::new();
+        crate::register_components(&mut world);
+        world.insert(SpatialGrid::new(8.0));
+        world.insert(WorldMsgQueue::<OverlapStarted>::new());
+        world.insert(WorldMsgQueue::<OverlapEnded>::new());
+        world
+    }
+
+    fn step(world: &World, system: &mut OverlapSystem) {
+        UpdateSpatialGridSystem {}.run_now(world);
+        system.run_now(world);
+    }
+
+    #[test]
+    pub fn test_overlap_started_and_ended() {
+        let mut world = create_world();
+        let circle = world.create_entity().with(Position { x: 0.0, y: 0.0 }).with(CircleCollider { radius: 2.0 }).build();
+        let aabb = world.create_entity().with(Position { x: 17.0, y: 0.0 }).with(AabbCollider { half_width: 16.0, half_height: 1.0 }).build();
+        let mut system = OverlapSystem::default();
+        step(&world, &mut system);
+        // The box reaches 1.0, which is inside of the circle even though the centers are several cells apart.
+        assert_eq!(world.fetch::<WorldMsgQueue<OverlapStarted>>().pop(), Some(OverlapStarted(circle, aabb)));
+        assert!(system.is_overlapping(aabb, circle));
+
+        world.write_storage::<Position>().get_mut(aabb).unwrap().x = 40.0;
+        step(&world, &mut system);
+        assert!(world.fetch::<WorldMsgQueue<OverlapStarted>>().pop().is_none());
+        assert_eq!(world.fetch::<WorldMsgQueue<OverlapEnded>>().pop(), Some(OverlapEnded(circle, aabb)));
+    }
+
+    #[test]
+    pub fn test_large_collider() {
+        let mut world = create_world();
+        let large = world.create_entity().with(Position { x: 0.0, y: 0.0 }).with(AabbCollider { half_width: 100.0, half_height: 100.0 }).build();
+        let a = world.create_entity().with(Position { x: 50.0, y: 50.0 }).with(CircleCollider { radius: 2.0 }).build();
+        let b = world.create_entity().with(Position { x: 53.0, y: 50.0 }).with(CircleCollider { radius: 2.0 }).build();
+        world.create_entity().with(Position { x: 500.0, y: 0.0 }).with(CircleCollider { radius: 2.0 }).build();
+        let mut system = OverlapSystem::default();
+        step(&world, &mut system);
+        assert!(system.is_overlapping(large, a));
+        assert!(system.is_overlapping(large, b));
+        assert!(system.is_overlapping(a, b));
+        assert_eq!(system.overlapping.len(), 3);
+    }
+
+    #[test]
+    pub fn test_collision_layers() {
+        let mut world = create_world();
+        let player = world.create_entity()
+            .with(Position { x: 0.0, y: 0.0 })
+            .with(CircleCollider { radius: 1.0 })
+            .with(CollisionLayers::new(0b01, 0b00))
+            .build();
+        world.create_entity()
+            .with(Position { x: 1.0, y: 0.0 })
+            .with(CircleCollider { radius: 1.0 })
+            .with(CollisionLayers::new(0b10, 0b00))
+            .build();
+        let pickup = world.create_entity()
+            .with(Position { x: -1.0, y: 0.0 })
+            .with(CircleCollider { radius: 1.0 })
+            .with(CollisionLayers::new(0b10, 0b01))
+            .build();
+        let mut system = OverlapSystem::default();
+        step(&world, &mut system);
+        let started = world.fetch::<WorldMsgQueue<OverlapStarted>>();
+        assert_eq!(started.pop(), Some(OverlapStarted(player, pickup)));
+        assert!(started.pop().is_none());
+    }
+}
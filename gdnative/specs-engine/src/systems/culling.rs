@@ -0,0 +1,85 @@
+use specs::prelude::*;
+use crate::components::*;
+use crate::resources::*;
+
+/// The number of entities that were culled by the last run of the `CullingSystem`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CullingStats {
+    pub culled: usize,
+    pub visible: usize,
+}
+
+/// Adds `Culled` to every entity with `CullBounds` that is entirely outside of the `ViewRect`, and removes it from the ones that are not.
+/// `margin` grows the view so that entities are revealed slightly before they come into view.
+// Note: The `ViewRect` resource MUST be added to the simulation for this system to work.
+pub struct CullingSystem {
+    pub margin: f32,
+}
+
+impl <'a> System <'a> for CullingSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, ViewRect>,
+        Write<'a, CullingStats>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, CullBounds>,
+        WriteStorage<'a, Culled>,
+    );
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, view, mut stats, positions, bounds, mut culled) = data;
+        let rect = view.0;
+        let (min_x, min_y) = (rect.x() - self.margin, rect.y() - self.margin);
+        let (max_x, max_y) = (rect.x_max() + self.margin, rect.y_max() + self.margin);
+        let mut changes = Vec::new();
+        *stats = CullingStats::default();
+        for (entity, position, bounds) in (&entities, &positions, &bounds).join() {
+            let outside = position.x + bounds.half_width < min_x || position.x - bounds.half_width > max_x ||
+                position.y + bounds.half_height < min_y || position.y - bounds.half_height > max_y;
+            if outside {
+                stats.culled += 1;
+            } else {
+                stats.visible += 1;
+            }
+            // Only entities that change are touched so that the change events can be used to update the visibility.
+            if outside != culled.contains(entity) {
+                changes.push((entity, outside));
+            }
+        }
+        for (entity, outside) in changes {
+            if outside {
+                culled.insert(entity, Culled).expect("the entity should be alive");
+            } else {
+                culled.remove(entity);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_culling() {
+        let mut world = World::new();
+        crate::register_components(&mut world);
+        world.insert(ViewRect(BoundingBox::new(0.0, 0.0, 100.0, 100.0)));
+        world.insert(CullingStats::default());
+        let inside = world.create_entity().with(Position { x: 50.0, y: 50.0 }).with(CullBounds { half_width: 5.0, half_height: 5.0 }).build();
+        let overlapping = world.create_entity().with(Position { x: 112.0, y: 50.0 }).with(CullBounds { half_width: 5.0, half_height: 5.0 }).build();
+        let outside = world.create_entity().with(Position { x: 50.0, y: -20.0 }).with(CullBounds { half_width: 5.0, half_height: 5.0 }).build();
+        CullingSystem { margin: 10.0 }.run_now(&world);
+        {
+            let culled = world.read_storage::<Culled>();
+            assert!(!culled.contains(inside));
+            assert!(!culled.contains(overlapping));
+            assert!(culled.contains(outside));
+            assert_eq!(world.read_resource::<CullingStats>().culled, 1);
+        }
+
+        world.write_storage::<Position>().get_mut(outside).unwrap().y = 50.0;
+        CullingSystem { margin: 10.0 }.run_now(&world);
+        assert!(!world.read_storage::<Culled>().contains(outside));
+        assert_eq!(world.read_resource::<CullingStats>().visible, 3);
+    }
+}
@@ -0,0 +1,83 @@
+use specs::prelude::*;
+use crate::components::*;
+use crate::resources::*;
+
+const VELOCITY_COLOR: DebugColor = [0.2, 0.6, 1.0, 1.0];
+const BOUNDS_COLOR: DebugColor = [1.0, 1.0, 0.0, 1.0];
+const COLLIDER_COLOR: DebugColor = [0.2, 1.0, 0.2, 1.0];
+const PATH_COLOR: DebugColor = [1.0, 0.4, 0.8, 1.0];
+
+/// Pushes the velocities, the `BoundingBox`, the colliders and the paths of every entity into the `DebugDraw` while it is enabled.
+/// Velocities are drawn as the distance the entity will move in `velocity_scale` seconds.
+// Note: The `DebugDraw` resource MUST be added to the simulation for this system to work.
+pub struct DebugDrawSystem {
+    pub velocity_scale: f32,
+}
+
+impl Default for DebugDrawSystem {
+    fn default() -> Self {
+        Self { velocity_scale: 0.25 }
+    }
+}
+
+impl <'a> System <'a> for DebugDrawSystem {
+    type SystemData = (
+        ReadExpect<'a, DebugDraw>,
+        Option<Read<'a, BoundingBox>>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, Velocity>,
+        ReadStorage<'a, CircleCollider>,
+        ReadStorage<'a, AabbCollider>,
+        ReadStorage<'a, PathFollow>,
+    );
+    fn run(&mut self, data: Self::SystemData) {
+        let (debug_draw, bounding_box, positions, velocities, circles, aabbs, paths) = data;
+        if !debug_draw.is_enabled() {
+            return;
+        }
+        if let Some(bounds) = bounding_box {
+            debug_draw.rect(bounds.x(), bounds.y(), bounds.width(), bounds.height(), BOUNDS_COLOR);
+        }
+        for (position, velocity) in (&positions, &velocities).join() {
+            let to = (position.x + velocity.x * self.velocity_scale, position.y + velocity.y * self.velocity_scale);
+            debug_draw.line((position.x, position.y), to, VELOCITY_COLOR);
+        }
+        for (position, circle) in (&positions, &circles).join() {
+            debug_draw.circle(position.x, position.y, circle.radius, COLLIDER_COLOR);
+        }
+        for (position, aabb) in (&positions, &aabbs).join() {
+            debug_draw.rect(position.x - aabb.half_width, position.y - aabb.half_height, aabb.half_width * 2.0, aabb.half_height * 2.0, COLLIDER_COLOR);
+        }
+        for (position, path) in (&positions, &paths).join() {
+            let mut from = (position.x, position.y);
+            for &to in path.waypoints().iter().skip(path.next) {
+                debug_draw.line(from, to, PATH_COLOR);
+                from = to;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_debug_draw() {
+        let mut world = World::new();
+        crate::register_components(&mut world);
+        world.insert(DebugDraw::new());
+        world.create_entity().with(Position { x: 10.0, y: 10.0 }).with(Velocity { x: 4.0, y: 0.0 }).with(CircleCollider { radius: 2.0 }).build();
+        let mut system = DebugDrawSystem { velocity_scale: 0.5 };
+        system.run_now(&world);
+        assert!(world.read_resource::<DebugDraw>().drain().is_empty());
+
+        world.read_resource::<DebugDraw>().set_enabled(true);
+        system.run_now(&world);
+        let shapes = world.read_resource::<DebugDraw>().drain();
+        assert_eq!(shapes.len(), 2);
+        assert!(shapes.contains(&(DebugShape::Line { from: (10.0, 10.0), to: (12.0, 10.0), width: 1.0 }, VELOCITY_COLOR)));
+        assert!(shapes.contains(&(DebugShape::Circle { x: 10.0, y: 10.0, radius: 2.0 }, COLLIDER_COLOR)));
+        assert!(world.read_resource::<DebugDraw>().drain().is_empty());
+    }
+}
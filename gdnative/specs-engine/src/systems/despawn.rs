@@ -0,0 +1,93 @@
+//! This module contains the despawn pipeline. Entities are never deleted directly, instead a `DespawnRequest` is added and the following systems run in order:
+//! 1. `LifetimeSystem` requests a despawn for any entity whose `Lifetime` has run out.
+//! 2. `PropagateDespawnSystem` requests a despawn for all of the children in the `TreeRelationship`.
+//! 3. Any systems that need to clean up resources outside of the ECS (such as rids or Godot nodes) read the `DespawnRequest`s.
+//! 4. `DespawnSystem` deletes the entities.
+use specs::prelude::*;
+use crate::components::*;
+use crate::hierarchy::detach_from_parent;
+use crate::resources::*;
+
+pub struct LifetimeSystem {}
+
+impl <'a> System <'a> for LifetimeSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Time>,
+        WriteStorage<'a, Lifetime>,
+        WriteStorage<'a, DespawnRequest>,
+    );
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, time, mut lifetimes, mut despawn_requests) = data;
+        let mut expired = Vec::new();
+        for (entity, lifetime, _) in (&entities, &mut lifetimes, !&despawn_requests).join() {
+            lifetime.remaining -= time.delta;
+            if lifetime.remaining <= 0.0 {
+                expired.push(entity);
+            }
+        }
+        for entity in expired {
+            despawn_requests.insert(entity, DespawnRequest).expect("the entity should be alive");
+        }
+    }
+}
+
+/// Requests a despawn for every descendant of an entity that has requested a despawn.
+pub struct PropagateDespawnSystem {}
+
+impl <'a> System <'a> for PropagateDespawnSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, TreeRelationship>,
+        WriteStorage<'a, DespawnRequest>,
+    );
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, relationships, mut despawn_requests) = data;
+        let mut pending: Vec<Entity> = (&entities, &despawn_requests).join().map(|(entity, _)| entity).collect();
+        while let Some(entity) = pending.pop() {
+            if let Some(relationship) = relationships.get(entity) {
+                for child in relationship.children.iter() {
+                    if entities.is_alive(*child) && !despawn_requests.contains(*child) {
+                        despawn_requests.insert(*child, DespawnRequest).expect("the child should be alive");
+                        pending.push(*child);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Deletes every entity with a `DespawnRequest`. The deletion is committed on the next `World::maintain`.
+/// Entities are removed from the children of any parent that is not also being despawned so that no references are left dangling.
+pub struct DespawnSystem {}
+
+impl <'a> System <'a> for DespawnSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, DespawnRequest>,
+        WriteStorage<'a, TreeRelationship>,
+    );
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, despawn_requests, mut relationships) = data;
+        let despawned: Vec<Entity> = (&entities, &despawn_requests).join().map(|(entity, _)| entity).collect();
+        for entity in despawned {
+            // Parents that are also being despawned do not need to be updated.
+            let parent = relationships.get(entity).and_then(|r| r.parent);
+            if parent.is_some_and(|parent| !despawn_requests.contains(parent)) {
+                detach_from_parent(&mut relationships, entity);
+            }
+            // Children are only left behind if the `PropagateDespawnSystem` did not run, in which case they become roots.
+            let children = relationships.get_mut(entity).map(|r| std::mem::take(&mut r.children)).unwrap_or_default();
+            for child in children {
+                if !despawn_requests.contains(child) {
+                    if let Some(relationship) = relationships.get_mut(child) {
+                        relationship.parent = None;
+                    }
+                }
+            }
+            if let Err(err) = entities.delete(entity) {
+                log::warn!("could not despawn {:?}: {}", entity, err);
+            }
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use specs::prelude::*;
+use crate::components::*;
+
+/// Calculates the `DrawOrder` of every entity with a `ZIndex` or `YSort`, adding it if it does not exist.
+/// `YSort` entities are given draw indices in order of their z index and then their `Position.y`. As the draw index only orders an entity
+/// amongst its siblings, numbering every sorted entity together keeps the siblings in the correct order as well.
+pub struct DrawOrderSystem {}
+
+impl <'a> System <'a> for DrawOrderSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, ZIndex>,
+        ReadStorage<'a, YSort>,
+        ReadStorage<'a, Position>,
+        WriteStorage<'a, DrawOrder>,
+    );
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, z_indices, y_sorts, positions, mut draw_orders) = data;
+        let mut sorted: Vec<(i32, f32, Entity)> = (&entities, &y_sorts, &positions).join()
+            .map(|(entity, _, position)| (z_indices.get(entity).map_or(0, |z_index| z_index.0), position.y, entity))
+            .collect();
+        sorted.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)).then(a.2.id().cmp(&b.2.id())));
+        let mut updates: Vec<(Entity, DrawOrder)> = sorted.iter().enumerate()
+            .map(|(draw_index, &(z_index, _, entity))| (entity, DrawOrder { z_index, draw_index: draw_index as i32 }))
+            .collect();
+        for (entity, z_index, _) in (&entities, &z_indices, !&y_sorts).join() {
+            updates.push((entity, DrawOrder { z_index: z_index.0, draw_index: 0 }));
+        }
+        for (entity, draw_order) in updates {
+            // Reading first avoids flagging entities whose draw order has not changed.
+            if draw_orders.get(entity) != Some(&draw_order) {
+                draw_orders.insert(entity, draw_order).expect("the entity should be alive");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_draw_order() {
+        let mut world = World::new();
+        crate::register_components(&mut world);
+        let front = world.create_entity().with(YSort).with(Position { x: 0.0, y: 20.0 }).build();
+        let back = world.create_entity().with(YSort).with(Position { x: 0.0, y: 10.0 }).build();
+        let above = world.create_entity().with(YSort).with(ZIndex(1)).with(Position { x: 0.0, y: 0.0 }).build();
+        let mut reader = world.write_storage::<DrawOrder>().register_reader();
+        DrawOrderSystem {}.run_now(&world);
+        {
+            let draw_orders = world.read_storage::<DrawOrder>();
+            assert_eq!(draw_orders.get(back).unwrap().draw_index(), 0);
+            assert_eq!(draw_orders.get(front).unwrap().draw_index(), 1);
+            assert_eq!(draw_orders.get(above).unwrap().draw_index(), 2);
+            assert_eq!(draw_orders.get(above).unwrap().z_index(), 1);
+            assert_eq!(draw_orders.channel().read(&mut reader).count(), 3);
+        }
+
+        // Only the entities that swap places are changed.
+        world.write_storage::<Position>().get_mut(front).unwrap().y = 5.0;
+        DrawOrderSystem {}.run_now(&world);
+        let draw_orders = world.read_storage::<DrawOrder>();
+        assert_eq!(draw_orders.get(front).unwrap().draw_index(), 0);
+        assert_eq!(draw_orders.channel().read(&mut reader).count(), 2);
+    }
+}
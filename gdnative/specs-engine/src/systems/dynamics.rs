@@ -0,0 +1,176 @@
+//! This module contains a lightweight integrator for entities that need more than kinematic movement but not a full physics engine.
+//! Entities with an `Acceleration` will have their `Velocity` and `Position` integrated by the `IntegrateDynamicsSystem`.
+//! Entities without one are still moved by the `UpdatePositionSystem`, but their `Drag` and `MaxSpeed` are applied here.
+use specs::prelude::*;
+use crate::components::*;
+use crate::resources::*;
+
+/// An instantaneous change in momentum. The velocity of the entity is changed by the impulse divided by its `Mass`.
+#[derive(Debug, Clone, Copy)]
+pub struct Impulse(pub Entity, pub f32, pub f32);
+
+/// The method used to integrate the `Acceleration` into the `Velocity` and `Position`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Integrator {
+    /// Updates the velocity first and then uses the new velocity to update the position. This is cheap and stable for most games.
+    #[default]
+    SemiImplicitEuler,
+    /// Velocity Verlet, this also accounts for the change in velocity during the frame when updating the position.
+    /// This is more accurate for trajectories, such as projectiles under gravity.
+    Verlet,
+}
+
+/// Applies the queued impulses, then integrates the `Acceleration`, `Drag` and `MaxSpeed` of each entity.
+/// Entities without an `Acceleration` only have the `Drag` and `MaxSpeed` applied to their `Velocity`, so that bodies that are only moved by
+/// impulses still slow down.
+// Note: The `WorldMsgQueue<Impulse>` resource MUST be added to the simulation for this system to work.
+#[derive(Default)]
+pub struct IntegrateDynamicsSystem {
+    pub integrator: Integrator,
+}
+
+impl IntegrateDynamicsSystem {
+    pub fn new(integrator: Integrator) -> Self {
+        Self { integrator }
+    }
+}
+
+fn apply_limits(velocity: &mut Velocity, drag: Option<&Drag>, max_speed: Option<&MaxSpeed>, delta: f32) {
+    if let Some(drag) = drag {
+        let retained = (1.0 - drag.0.clamp(0.0, 1.0)).powf(delta);
+        velocity.x *= retained;
+        velocity.y *= retained;
+    }
+    if let Some(max_speed) = max_speed {
+        let speed = (velocity.x * velocity.x + velocity.y * velocity.y).sqrt();
+        if speed > max_speed.0 && speed > 0.0 {
+            velocity.x *= max_speed.0 / speed;
+            velocity.y *= max_speed.0 / speed;
+        }
+    }
+}
+
+impl <'a> System <'a> for IntegrateDynamicsSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        ReadExpect<'a, Time>,
+        WriteExpect<'a, WorldMsgQueue<Impulse>>,
+        ReadStorage<'a, Acceleration>,
+        ReadStorage<'a, Mass>,
+        ReadStorage<'a, Drag>,
+        ReadStorage<'a, MaxSpeed>,
+        WriteStorage<'a, Velocity>,
+        WriteStorage<'a, Position>,
+    );
+    fn run(&mut self, data: Self::SystemData) {
+        let (time, impulses, accelerations, masses, drags, max_speeds, mut velocities, mut positions) = data;
+        while let Some(Impulse(entity, x, y)) = impulses.pop() {
+            let mass = masses.get(entity).map_or(1.0, |mass| mass.0);
+            if mass <= 0.0 {
+                log::warn!("cannot apply an impulse to {:?} as its mass is not positive", entity);
+                continue;
+            }
+            if let Some(velocity) = velocities.get_mut(entity) {
+                velocity.x += x / mass;
+                velocity.y += y / mass;
+            }
+        }
+        let integrator = self.integrator;
+        let delta = time.delta;
+        (&accelerations, &mut velocities, &mut positions, drags.maybe(), max_speeds.maybe())
+            .par_join()
+            .for_each(|(acceleration, velocity, position, drag, max_speed)| {
+                let (old_x, old_y) = (velocity.x, velocity.y);
+                velocity.x += acceleration.x * delta;
+                velocity.y += acceleration.y * delta;
+                apply_limits(velocity, drag, max_speed, delta);
+                match integrator {
+                    Integrator::SemiImplicitEuler => {
+                        position.x += velocity.x * delta;
+                        position.y += velocity.y * delta;
+                    },
+                    Integrator::Verlet => {
+                        // Averaging the old and new velocities is equivalent to `x + v * dt + a * dt^2 / 2` but also accounts for the drag.
+                        position.x += (old_x + velocity.x) * 0.5 * delta;
+                        position.y += (old_y + velocity.y) * 0.5 * delta;
+                    },
+                }
+            });
+        let mut limited = drags.mask().clone();
+        limited |= max_speeds.mask();
+        (&mut velocities, drags.maybe(), max_speeds.maybe(), !&accelerations, &limited)
+            .par_join()
+            .for_each(|(velocity, drag, max_speed, _, _)| apply_limits(velocity, drag, max_speed, delta));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn create_world() -> World {
+        let mut world = World::new();
+        crate::register_components(&mut world);
+        world.insert(Time { delta: 0.1, total: 0.0 });
+        world.insert(WorldMsgQueue::<Impulse>::new());
+        world
+    }
+
+    fn create_falling(world: &mut World) -> Entity {
+        world.create_entity()
+            .with(Position { x: 0.0, y: 0.0 })
+            .with(Velocity { x: 0.0, y: 0.0 })
+            .with(Acceleration { x: 0.0, y: 10.0 })
+            .build()
+    }
+
+    #[test]
+    pub fn test_verlet_matches_constant_acceleration() {
+        let mut world = create_world();
+        let entity = create_falling(&mut world);
+        let mut system = IntegrateDynamicsSystem::new(Integrator::Verlet);
+        for _ in 0..10 {
+            system.run_now(&world);
+        }
+        // After one second, y = a * t^2 / 2 = 5.
+        let y = world.read_storage::<Position>().get(entity).unwrap().y;
+        assert!((y - 5.0).abs() < 0.001);
+    }
+
+    #[test]
+    pub fn test_impulse_and_limits() {
+        let mut world = create_world();
+        let entity = create_falling(&mut world);
+        world.write_storage::<Mass>().insert(entity, Mass(2.0)).unwrap();
+        world.write_storage::<MaxSpeed>().insert(entity, MaxSpeed(5.0)).unwrap();
+        world.fetch::<WorldMsgQueue<Impulse>>().push(Impulse(entity, 4.0, 0.0));
+        IntegrateDynamicsSystem::default().run_now(&world);
+        {
+            let velocities = world.read_storage::<Velocity>();
+            let velocity = velocities.get(entity).unwrap();
+            assert!((velocity.x - 2.0).abs() < 0.001);
+            assert!((velocity.y - 1.0).abs() < 0.001);
+        }
+        world.fetch::<WorldMsgQueue<Impulse>>().push(Impulse(entity, 100.0, 0.0));
+        IntegrateDynamicsSystem::default().run_now(&world);
+        let velocities = world.read_storage::<Velocity>();
+        let velocity = velocities.get(entity).unwrap();
+        assert!((velocity.x * velocity.x + velocity.y * velocity.y).sqrt() <= 5.0 + 0.001);
+    }
+
+    #[test]
+    pub fn test_drag_without_acceleration() {
+        let mut world = create_world();
+        let entity = world.create_entity()
+            .with(Position { x: 0.0, y: 0.0 })
+            .with(Velocity { x: 0.0, y: 0.0 })
+            .with(Drag(0.5))
+            .build();
+        world.fetch::<WorldMsgQueue<Impulse>>().push(Impulse(entity, 10.0, 0.0));
+        IntegrateDynamicsSystem::default().run_now(&world);
+        // The velocity is damped, but the position is left to the `UpdatePositionSystem`.
+        let velocity = world.read_storage::<Velocity>().get(entity).unwrap().x;
+        assert!((velocity - 10.0 * 0.5f32.powf(0.1)).abs() < 0.001);
+        assert_eq!(world.read_storage::<Position>().get(entity).unwrap().x, 0.0);
+    }
+}
@@ -29,10 +29,10 @@ impl <'a> System <'a> for ChangeVelocityAtBounds {
         ReadStorage<'a, Position>,);
     fn run(&mut self, data: Self::SystemData) {
         let (bounding_box, time, mut velocities, positions) = data;
-        let x_min = bounding_box.x;
-        let y_min = bounding_box.y;
-        let x_max = bounding_box.x + bounding_box.width;
-        let y_max = bounding_box.y + bounding_box.height;
+        let x_min = bounding_box.x();
+        let y_min = bounding_box.y();
+        let x_max = bounding_box.x_max();
+        let y_max = bounding_box.y_max();
         for (velocity, position) in (&mut velocities, &positions).join() {
             let future_x = position.x + velocity.x * time.delta;
             let future_y = position.y + velocity.y * time.delta;
@@ -47,16 +47,6 @@ impl <'a> System <'a> for ChangeVelocityAtBounds {
     }
 }
 
-/// Simple bounding box starting at the coorindate `x`, `y` and extending out `width` and `height`. `width` and `height` must be non-zero.
-pub struct BoundingBox { x: f32, y: f32, width: f32, height: f32 }
-impl BoundingBox {
-    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
-        assert!(width > 0f32);
-        assert!(height > 0f32);
-        Self { x, y, width, height }
-    }
-}
-
 pub struct UpdatePositionWithBoundsSystem {}
 // Note: If you have a more physicy game, you may wish to base Velocity off of Acceleration.
 impl <'a> System <'a> for UpdatePositionWithBoundsSystem {
@@ -69,8 +59,8 @@ impl <'a> System <'a> for UpdatePositionWithBoundsSystem {
         let (bounding_box, velocities, mut positions) = data;
         
         for (position, velocity) in (&mut positions, &velocities).join() {
-            position.x = f32::clamp(position.x + velocity.x, bounding_box.x, bounding_box.x + bounding_box.width);
-            position.y = f32::clamp(position.y + velocity.y, bounding_box.y, bounding_box.y + bounding_box.height);
+            position.x = f32::clamp(position.x + velocity.x, bounding_box.x(), bounding_box.x_max());
+            position.y = f32::clamp(position.y + velocity.y, bounding_box.y(), bounding_box.y_max());
         }
     }
 }
@@ -89,8 +79,8 @@ impl <'a> System <'a> for UpdateBoundedPositionSystem {
         let (bounding_box, stay_inside, velocities, mut positions) = data;
         
         for (position, velocity, _) in (&mut positions, &velocities, &stay_inside).join() {
-            position.x = f32::clamp(position.x + velocity.x, bounding_box.x, bounding_box.x + bounding_box.width);
-            position.y = f32::clamp(position.y+ velocity.y, bounding_box.y, bounding_box.y + bounding_box.height);
+            position.x = f32::clamp(position.x + velocity.x, bounding_box.x(), bounding_box.x_max());
+            position.y = f32::clamp(position.y+ velocity.y, bounding_box.y(), bounding_box.y_max());
         }
     }
 }
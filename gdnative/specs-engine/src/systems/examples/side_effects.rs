@@ -1,5 +1,6 @@
 use specs::prelude::*;
 use crate::components::Counter;
+use crate::resources::WorldMsgQueue;
 
 pub enum SideEffect {
     SetZero,
@@ -9,7 +10,18 @@ pub enum SideEffect {
     Subtract { amount: i32 },
 }
 
-declare_msg_queue!(SideEffectQueue, (Entity, SideEffect));
+impl SideEffect {
+    /// Resetting the counter does not commute with the other side effects, so it is always applied first.
+    fn order(&self) -> u8 {
+        match self {
+            SideEffect::SetZero => 0,
+            _ => 1,
+        }
+    }
+}
+
+/// The side effects are pushed from systems that run in parallel, so they arrive in a different order each run.
+pub type SideEffectQueue = WorldMsgQueue<(Entity, SideEffect)>;
 
 pub struct CountModifier1System {}
 impl <'a> System <'a> for CountModifier1System {
@@ -96,7 +108,8 @@ impl <'a> System <'a> for CounterSideEffectsSystem {
             queue,
             mut counters
         ) = data;
-        while let Some((entity, side_effect)) = queue.pop() {
+        let side_effects = queue.drain_sorted_by_key(|(entity, side_effect)| (entity.id(), side_effect.order()));
+        for (entity, side_effect) in side_effects {
             if let Some(counter) = counters.get_mut(entity) {
                 match side_effect {
                     SideEffect::SetZero => { counter.0 = 0; },
@@ -0,0 +1,64 @@
+//! This module contains the systems that turn the `InputState` into intents.
+use specs::prelude::*;
+use crate::components::*;
+use crate::input::InputState;
+
+/// Sets the `SetVelocityIntent` of every `Player` from two axes of the `InputState`.
+/// The direction is limited to a length of 1 so that moving diagonally is not faster.
+// Note: The `InputState` resource MUST be added to the simulation for this system to work.
+pub struct PlayerMovementSystem {
+    pub speed: f32,
+    pub axis_x: &'static str,
+    pub axis_y: &'static str,
+}
+
+impl PlayerMovementSystem {
+    /// Uses the `move_x` and `move_y` axes.
+    pub fn new(speed: f32) -> Self {
+        Self { speed, axis_x: "move_x", axis_y: "move_y" }
+    }
+}
+
+impl <'a> System <'a> for PlayerMovementSystem {
+    type SystemData = (
+        ReadExpect<'a, InputState>,
+        ReadStorage<'a, Player>,
+        WriteStorage<'a, SetVelocityIntent>,
+    );
+    fn run(&mut self, data: Self::SystemData) {
+        let (input, players, mut intents) = data;
+        let (mut x, mut y) = (input.axis(self.axis_x), input.axis(self.axis_y));
+        let length = (x * x + y * y).sqrt();
+        if length > 1.0 {
+            x /= length;
+            y /= length;
+        }
+        for (intent, _) in (&mut intents, &players).join() {
+            intent.x = x * self.speed;
+            intent.y = y * self.speed;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_player_movement() {
+        let mut world = World::new();
+        crate::register_components(&mut world);
+        let mut input = InputState::new();
+        input.set_axis("move_x", 1.0);
+        input.set_axis("move_y", 1.0);
+        world.insert(input);
+        let player = world.create_entity().with(Player).with(SetVelocityIntent { x: 0.0, y: 0.0 }).build();
+        let other = world.create_entity().with(SetVelocityIntent { x: 0.0, y: 0.0 }).build();
+        PlayerMovementSystem::new(10.0).run_now(&world);
+        let intents = world.read_storage::<SetVelocityIntent>();
+        let intent = intents.get(player).unwrap();
+        assert!((intent.x - 10.0 / 2f32.sqrt()).abs() < 0.001);
+        assert!((intent.y - 10.0 / 2f32.sqrt()).abs() < 0.001);
+        assert_eq!(intents.get(other).unwrap().x, 0.0);
+    }
+}
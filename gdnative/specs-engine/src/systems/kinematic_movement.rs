@@ -4,16 +4,17 @@ use specs::prelude::*;
 use crate::components::*;
 use crate::resources::*;
 pub struct UpdatePositionSystem {}
-// Note: If you have a more physicy game, you may wish to base Velocity off of Acceleration.
+// Note: Entities with an `Acceleration` are skipped as they are moved by the `IntegrateDynamicsSystem`.
 impl <'a> System <'a> for UpdatePositionSystem {
     type SystemData = (
         ReadExpect<'a, Time>,
         ReadStorage<'a, Velocity>,
+        ReadStorage<'a, Acceleration>,
         WriteStorage<'a, Position>
     );
     fn run(&mut self, data: Self::SystemData) {
-        let (time, velocities, mut positions) = data;
-        (&mut positions, &velocities).par_join().for_each(|(position, velocity)| {
+        let (time, velocities, accelerations, mut positions) = data;
+        (&mut positions, &velocities, !&accelerations).par_join().for_each(|(position, velocity, _)| {
                position.x += velocity.x * time.delta;
                position.y += velocity.y * time.delta;
            }
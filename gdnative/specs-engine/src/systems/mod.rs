@@ -1,5 +1,62 @@
+mod animation;
+pub use animation::*;
+
+mod behavior;
+pub use behavior::*;
+
+mod boundary;
+pub use boundary::*;
+
+mod camera;
+pub use camera::*;
+
+mod collision;
+pub use collision::*;
+
+mod culling;
+pub use culling::*;
+
+mod debug;
+pub use debug::*;
+
+mod despawn;
+pub use despawn::*;
+
+mod draw_order;
+pub use draw_order::*;
+
+mod dynamics;
+pub use dynamics::*;
+
 mod examples;
 pub use examples::*;
 
+mod input;
+pub use input::*;
+
 mod kinematic_movement;
-pub use kinematic_movement::*;
\ No newline at end of file
+pub use kinematic_movement::*;
+
+mod naming;
+pub use naming::*;
+
+mod navigation;
+pub use navigation::*;
+
+mod spatial;
+pub use spatial::*;
+
+mod state_machine;
+pub use state_machine::*;
+
+mod steering;
+pub use steering::*;
+
+mod timers;
+pub use timers::*;
+
+mod tweens;
+pub use tweens::*;
+
+mod utility;
+pub use utility::*;
\ No newline at end of file
@@ -0,0 +1,90 @@
+//! This module contains the system that keeps the `NameIndex` in sync with the `Name` and `Tags` components.
+use specs::prelude::*;
+use crate::components::*;
+use crate::naming::NameIndex;
+
+/// Reads the component events of `Name` and `Tags` to update the `NameIndex`.
+/// This system must be set up with `Dispatcher::setup` / `System::setup` before it is run.
+// Note: The `NameIndex` resource MUST be added to the simulation for this system to work.
+#[derive(Default)]
+pub struct NameIndexSystem {
+    name_reader: Option<ReaderId<ComponentEvent>>,
+    tag_reader: Option<ReaderId<ComponentEvent>>,
+}
+
+impl <'a> System <'a> for NameIndexSystem {
+    type SystemData = (
+        Entities<'a>,
+        WriteExpect<'a, NameIndex>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, Tags>,
+    );
+    fn setup(&mut self, world: &mut World) {
+        Self::SystemData::setup(world);
+        self.name_reader = Some(WriteStorage::<Name>::fetch(world).register_reader());
+        self.tag_reader = Some(WriteStorage::<Tags>::fetch(world).register_reader());
+    }
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, mut index, names, tags) = data;
+        let name_reader = self.name_reader.as_mut().expect("NameIndexSystem::setup has not been called");
+        for event in names.channel().read(name_reader) {
+            match event {
+                ComponentEvent::Inserted(id) | ComponentEvent::Modified(id) => {
+                    let entity = entities.entity(*id);
+                    if let Some(name) = names.get(entity) {
+                        index.set_name(entity, &name.0);
+                    }
+                },
+                ComponentEvent::Removed(id) => index.remove_name(*id),
+            }
+        }
+        let tag_reader = self.tag_reader.as_mut().expect("NameIndexSystem::setup has not been called");
+        for event in tags.channel().read(tag_reader) {
+            match event {
+                ComponentEvent::Inserted(id) | ComponentEvent::Modified(id) => {
+                    let entity = entities.entity(*id);
+                    if let Some(entity_tags) = tags.get(entity) {
+                        index.set_tags(entity, entity_tags);
+                    }
+                },
+                ComponentEvent::Removed(id) => index.remove_tags(*id),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::naming::{FindByName, FindByTag};
+    use crate::util::WorldQuery;
+
+    #[test]
+    pub fn test_name_index() {
+        let mut world = World::new();
+        crate::register_components(&mut world);
+        world.insert(NameIndex::new());
+        let mut system = NameIndexSystem::default();
+        System::setup(&mut system, &mut world);
+
+        let player = world.create_entity()
+            .with(Name("player".to_owned()))
+            .with(Tags(vec!["friendly".to_owned()].into_iter().collect()))
+            .build();
+        let enemy = world.create_entity()
+            .with(Tags(vec!["hostile".to_owned(), "flying".to_owned()].into_iter().collect()))
+            .build();
+        system.run_now(&world);
+        assert_eq!(FindByName::query(&world, "player".to_owned()), Some(player));
+        assert_eq!(FindByTag::query(&world, "flying".to_owned()), vec![enemy]);
+
+        world.write_storage::<Name>().get_mut(player).unwrap().0 = "hero".to_owned();
+        world.delete_entity(enemy).unwrap();
+        world.maintain();
+        system.run_now(&world);
+        assert_eq!(FindByName::query(&world, "player".to_owned()), None);
+        assert_eq!(FindByName::query(&world, "hero".to_owned()), Some(player));
+        assert!(FindByTag::query(&world, "flying".to_owned()).is_empty());
+        assert_eq!(FindByTag::query(&world, "friendly".to_owned()), vec![player]);
+    }
+}
@@ -0,0 +1,178 @@
+//! This module contains the systems used to find and follow paths on the `NavGrid`, either per entity or through a shared `FlowField`.
+use specs::prelude::*;
+use specs::rayon::prelude::*;
+use crate::components::*;
+use crate::resources::*;
+
+/// Requests a path from the entity's current `Position` to the point. The entity must have a `PathFollow`.
+#[derive(Debug, Clone, Copy)]
+pub struct PathRequest(pub Entity, pub f32, pub f32);
+
+/// Emitted when a `PathRequest` could not be completed as there is no path to the point.
+#[derive(Debug, Clone, Copy)]
+pub struct PathNotFound(pub Entity);
+
+/// The path found for each entity, or `None` if the point cannot be reached.
+type FoundPaths = Vec<(Entity, Option<Vec<(f32, f32)>>)>;
+
+/// Computes the queued `PathRequest`s in parallel and sets the path on the `PathFollow` of each entity.
+/// At most `max_per_frame` requests are handled each time the system runs, the rest are left in the queue for later frames.
+// Note: The `NavGrid`, `WorldMsgQueue<PathRequest>` and `WorldMsgQueue<PathNotFound>` resources MUST be added to the simulation for this system to work.
+pub struct PathRequestSystem {
+    pub max_per_frame: usize,
+}
+
+impl PathRequestSystem {
+    pub fn new(max_per_frame: usize) -> Self {
+        Self { max_per_frame }
+    }
+}
+
+impl <'a> System <'a> for PathRequestSystem {
+    type SystemData = (
+        ReadExpect<'a, NavGrid>,
+        WriteExpect<'a, WorldMsgQueue<PathRequest>>,
+        ReadExpect<'a, WorldMsgQueue<PathNotFound>>,
+        ReadStorage<'a, Position>,
+        WriteStorage<'a, PathFollow>,
+    );
+    fn run(&mut self, data: Self::SystemData) {
+        let (grid, requests, not_found, positions, mut path_follows) = data;
+        let mut pending = Vec::new();
+        while pending.len() < self.max_per_frame {
+            match requests.pop() {
+                Some(PathRequest(entity, x, y)) => {
+                    match positions.get(entity) {
+                        Some(position) if path_follows.contains(entity) => pending.push((entity, (position.x, position.y), (x, y))),
+                        _ => log::warn!("cannot find a path for {:?} as it does not have a Position and PathFollow", entity),
+                    }
+                },
+                None => break,
+            }
+        }
+        let grid = &*grid;
+        let paths: FoundPaths = pending
+            .into_par_iter()
+            .map(|(entity, from, to)| (entity, grid.find_path(from, to)))
+            .collect();
+        for (entity, path) in paths {
+            match (path, path_follows.get_mut(entity)) {
+                (Some(path), Some(path_follow)) => path_follow.set_path(path),
+                (None, _) => not_found.push(PathNotFound(entity)),
+                (Some(_), None) => {},
+            }
+        }
+    }
+}
+
+/// Steers each entity with a `PathFollow` towards its next waypoint by setting the `SetVelocityIntent`.
+/// When the last waypoint is reached the intent is set to zero and the path is cleared.
+pub struct PathFollowSystem {}
+
+impl <'a> System <'a> for PathFollowSystem {
+    type SystemData = (
+        ReadStorage<'a, Position>,
+        WriteStorage<'a, PathFollow>,
+        WriteStorage<'a, SetVelocityIntent>,
+    );
+    fn run(&mut self, data: Self::SystemData) {
+        let (positions, mut path_follows, mut intents) = data;
+        (&positions, &mut path_follows, &mut intents)
+            .par_join()
+            .for_each(|(position, path_follow, intent)| {
+                if path_follow.waypoints.is_empty() {
+                    return;
+                }
+                while let Some((x, y)) = path_follow.next_waypoint() {
+                    let (dx, dy) = (x - position.x, y - position.y);
+                    let distance = (dx * dx + dy * dy).sqrt();
+                    if distance > path_follow.arrive_distance {
+                        intent.x = dx / distance * path_follow.speed;
+                        intent.y = dy / distance * path_follow.speed;
+                        return;
+                    }
+                    path_follow.next += 1;
+                }
+                intent.x = 0.0;
+                intent.y = 0.0;
+                path_follow.waypoints.clear();
+                path_follow.next = 0;
+            });
+    }
+}
+
+/// Brings the `FlowField` up to date with its goals and the `NavGrid`. This does nothing if neither has changed.
+// Note: The `NavGrid` and `FlowField` resources MUST be added to the simulation for this system to work.
+pub struct UpdateFlowFieldSystem {}
+
+impl <'a> System <'a> for UpdateFlowFieldSystem {
+    type SystemData = (
+        ReadExpect<'a, NavGrid>,
+        WriteExpect<'a, FlowField>,
+    );
+    fn run(&mut self, data: Self::SystemData) {
+        let (grid, mut flow_field) = data;
+        flow_field.update(&grid);
+    }
+}
+
+/// Sets the `Velocity` of each entity with a `FollowFlowField` from the direction of the `FlowField` at its `Position`.
+/// Entities that are on a goal or cannot reach one are stopped.
+pub struct FollowFlowFieldSystem {}
+
+impl <'a> System <'a> for FollowFlowFieldSystem {
+    type SystemData = (
+        ReadExpect<'a, NavGrid>,
+        ReadExpect<'a, FlowField>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, FollowFlowField>,
+        WriteStorage<'a, Velocity>,
+    );
+    fn run(&mut self, data: Self::SystemData) {
+        let (grid, flow_field, positions, follows, mut velocities) = data;
+        (&positions, &follows, &mut velocities)
+            .par_join()
+            .for_each(|(position, follow, velocity)| {
+                let (x, y) = flow_field.direction(&grid, position.x, position.y).unwrap_or((0.0, 0.0));
+                velocity.x = x * follow.speed;
+                velocity.y = y * follow.speed;
+            });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_path_request_and_follow() {
+        let mut world = World::new();
+        crate::register_components(&mut world);
+        let mut grid = NavGrid::new(4, 4, 10.0);
+        grid.set_walkable((1, 0), false);
+        grid.set_walkable((1, 1), false);
+        world.insert(grid);
+        world.insert(WorldMsgQueue::<PathRequest>::new());
+        world.insert(WorldMsgQueue::<PathNotFound>::new());
+        let entity = world.create_entity()
+            .with(Position { x: 5.0, y: 5.0 })
+            .with(SetVelocityIntent { x: 0.0, y: 0.0 })
+            .with(PathFollow::new(20.0, 1.0))
+            .build();
+        let blocked = world.create_entity()
+            .with(Position { x: 5.0, y: 5.0 })
+            .with(PathFollow::new(20.0, 1.0))
+            .build();
+        world.fetch::<WorldMsgQueue<PathRequest>>().push(PathRequest(entity, 25.0, 5.0));
+        world.fetch::<WorldMsgQueue<PathRequest>>().push(PathRequest(blocked, 15.0, 5.0));
+        PathRequestSystem::new(8).run_now(&world);
+        assert_eq!(world.fetch::<WorldMsgQueue<PathNotFound>>().pop().map(|event| event.0), Some(blocked));
+
+        // The wall forces the path to start by going down.
+        PathFollowSystem {}.run_now(&world);
+        let intents = world.read_storage::<SetVelocityIntent>();
+        let intent = intents.get(entity).unwrap();
+        assert!(intent.y > 0.0);
+        assert!(!world.read_storage::<PathFollow>().get(entity).unwrap().is_finished());
+    }
+}
@@ -0,0 +1,86 @@
+//! This module contains the system used to keep the `SpatialGrid` up to date.
+use specs::prelude::*;
+use crate::components::*;
+use crate::resources::*;
+
+/// Rebuilds the `SpatialGrid` from the `Position` of every entity.
+/// The cells are calculated in parallel and then inserted into the grid, which is the only part that must happen on one thread.
+// Note: The `SpatialGrid` resource MUST be added to the simulation for this system to work.
+pub struct UpdateSpatialGridSystem {}
+
+impl <'a> System <'a> for UpdateSpatialGridSystem {
+    type SystemData = (
+        Entities<'a>,
+        WriteExpect<'a, SpatialGrid>,
+        ReadStorage<'a, Position>,
+    );
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, mut grid, positions) = data;
+        let entries: Vec<((i32, i32), GridEntry)> = {
+            let grid = &*grid;
+            (&entities, &positions)
+                .par_join()
+                .map(|(entity, position)| (grid.cell_of(position.x, position.y), GridEntry { entity, x: position.x, y: position.y }))
+                .collect()
+        };
+        grid.clear();
+        grid.insert_all(entries);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::util::WorldQuery;
+
+    #[test]
+    pub fn test_spatial_grid_matches_brute_force() {
+        let mut world = World::new();
+        crate::register_components(&mut world);
+        world.insert(SpatialGrid::new(16.0));
+        // A simple linear congruential generator keeps the positions deterministic.
+        let mut seed = 12345u32;
+        let mut random = move || {
+            seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+            (seed >> 16) as f32 / 65536.0 * 200.0 - 100.0
+        };
+        for _ in 0..500 {
+            world.create_entity().with(Position { x: random(), y: random() }).build();
+        }
+        UpdateSpatialGridSystem {}.run_now(&world);
+
+        let (x, y, radius) = (12.5, -30.0, 25.0);
+        let mut found = EntitiesInRadius::query(&world, (x, y, radius));
+        let mut expected: Vec<Entity> = (&world.entities(), &world.read_storage::<Position>()).join()
+            .filter(|(_, p)| (p.x - x) * (p.x - x) + (p.y - y) * (p.y - y) <= radius * radius)
+            .map(|(e, _)| e)
+            .collect();
+        found.sort();
+        expected.sort();
+        assert!(!expected.is_empty());
+        assert_eq!(found, expected);
+
+        let mut found = EntitiesInRect::query(&world, (-50.0, -50.0, 30.0, 70.0));
+        let mut expected: Vec<Entity> = (&world.entities(), &world.read_storage::<Position>()).join()
+            .filter(|(_, p)| p.x >= -50.0 && p.x <= -20.0 && p.y >= -50.0 && p.y <= 20.0)
+            .map(|(e, _)| e)
+            .collect();
+        found.sort();
+        expected.sort();
+        assert_eq!(found, expected);
+    }
+
+    #[test]
+    pub fn test_spatial_grid_unbounded_queries() {
+        let mut world = World::new();
+        crate::register_components(&mut world);
+        world.insert(SpatialGrid::new(1.0));
+        let near = world.create_entity().with(Position { x: 0.5, y: 0.5 }).build();
+        let far = world.create_entity().with(Position { x: 1.0e6, y: -1.0e6 }).build();
+        UpdateSpatialGridSystem {}.run_now(&world);
+        let mut found = EntitiesInRadius::query(&world, (0.0, 0.0, f32::INFINITY));
+        found.sort();
+        assert_eq!(found, vec![near, far]);
+        assert_eq!(EntitiesInRect::query(&world, (0.0, 0.0, 1.0e9, 1.0e9)), vec![near]);
+    }
+}
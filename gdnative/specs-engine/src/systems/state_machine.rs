@@ -0,0 +1,203 @@
+//! This module contains the system used to advance `StateMachine`s.
+use std::marker::PhantomData;
+use specs::prelude::*;
+use crate::commands::DeferredCommand;
+use crate::components::*;
+use crate::resources::*;
+
+/// Requests that the entity's `StateMachine<S>` transitions to the state. Requests for the current state are ignored.
+#[derive(Debug, Clone, Copy)]
+pub struct TransitionRequest<S>(pub Entity, pub S);
+
+fn transition<S: State>(entity: Entity, machine: &mut StateMachine<S>, to: S, now: f32, commands: &WorldMsgQueue<DeferredCommand>) {
+    if machine.current == to {
+        return;
+    }
+    let from = machine.current;
+    // A request can arrive before the machine has been updated, in which case the initial state was never entered.
+    if machine.entered {
+        if let Some(command) = from.on_exit(entity) {
+            commands.push(command);
+        }
+    }
+    machine.entered = true;
+    machine.current = to;
+    machine.time_in_state = 0.0;
+    if let Some(command) = to.on_enter(entity) {
+        commands.push(command);
+    }
+    if machine.history_capacity > 0 {
+        if machine.history.len() == machine.history_capacity {
+            machine.history.pop_front();
+        }
+        machine.history.push_back(StateTransition { from, to, at: now });
+    }
+}
+
+/// Applies the queued `TransitionRequest`s and then runs the update hook of every `StateMachine<S>`.
+/// A separate system is needed for each type of state.
+// Note: Both the `WorldMsgQueue<TransitionRequest<S>>` and `WorldMsgQueue<DeferredCommand>` resources MUST be added to the simulation for this system to work.
+pub struct StateMachineSystem<S> {
+    phantom: PhantomData<S>,
+}
+
+impl <S> Default for StateMachineSystem<S> {
+    fn default() -> Self {
+        Self { phantom: PhantomData }
+    }
+}
+
+impl <'a, S: State> System <'a> for StateMachineSystem<S> {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Time>,
+        WriteExpect<'a, WorldMsgQueue<TransitionRequest<S>>>,
+        ReadExpect<'a, WorldMsgQueue<DeferredCommand>>,
+        WriteStorage<'a, StateMachine<S>>,
+        WriteStorage<'a, CurrentState>,
+    );
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, time, requests, commands, mut machines, mut current_states) = data;
+        while let Some(TransitionRequest(entity, to)) = requests.pop() {
+            if let Some(machine) = machines.get_mut(entity) {
+                transition(entity, machine, to, time.total, &commands);
+            } else {
+                log::warn!("cannot transition {:?} to {:?} as it does not have a state machine", entity, to);
+            }
+        }
+        (&entities, &mut machines).par_join().for_each(|(entity, machine)| {
+            if !machine.entered {
+                machine.entered = true;
+                if let Some(command) = machine.current.on_enter(entity) {
+                    commands.push(command);
+                }
+            }
+            machine.time_in_state += time.delta;
+            if let Some(to) = machine.current.on_update(entity, machine.time_in_state) {
+                transition(entity, machine, to, time.total, &commands);
+            }
+        });
+        for (entity, machine) in (&entities, &machines).join() {
+            let name = machine.current.name();
+            match current_states.get_mut(entity) {
+                Some(current_state) if current_state.name == name => {},
+                Some(current_state) => current_state.name = name.to_owned(),
+                None => {
+                    current_states.insert(entity, CurrentState { name: name.to_owned() }).expect("the entity should be alive");
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::commands::ApplyDeferredCommands;
+    use crate::util::WorldCommand;
+    use specs_derive::Component;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Door {
+        Closed,
+        Opening,
+        Open,
+    }
+
+    struct CountEnters {}
+
+    impl WorldCommand for CountEnters {
+        type Args = Entity;
+        type Output = ();
+        fn execute(world: &mut World, entity: Self::Args) -> Self::Output {
+            if let Some(counter) = world.write_storage::<Counter>().get_mut(entity) {
+                counter.0 += 1;
+            }
+        }
+    }
+
+    #[derive(Debug, Default, Component)]
+    struct ExitCount(u32);
+
+    struct CountExits {}
+
+    impl WorldCommand for CountExits {
+        type Args = Entity;
+        type Output = ();
+        fn execute(world: &mut World, entity: Self::Args) -> Self::Output {
+            if let Some(count) = world.write_storage::<ExitCount>().get_mut(entity) {
+                count.0 += 1;
+            }
+        }
+    }
+
+    impl State for Door {
+        fn name(&self) -> &'static str {
+            match self {
+                Door::Closed => "closed",
+                Door::Opening => "opening",
+                Door::Open => "open",
+            }
+        }
+        fn on_enter(&self, entity: Entity) -> Option<DeferredCommand> {
+            Some(DeferredCommand::new::<CountEnters>(entity))
+        }
+        fn on_exit(&self, entity: Entity) -> Option<DeferredCommand> {
+            Some(DeferredCommand::new::<CountExits>(entity))
+        }
+        fn on_update(&self, _: Entity, time_in_state: f32) -> Option<Self> {
+            match self {
+                Door::Opening if time_in_state >= 1.0 => Some(Door::Open),
+                _ => None,
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_state_machine() {
+        let mut world = World::new();
+        crate::register_components(&mut world);
+        world.register::<StateMachine<Door>>();
+        world.register::<ExitCount>();
+        world.insert(Time { delta: 0.5, total: 0.0 });
+        world.insert(WorldMsgQueue::<TransitionRequest<Door>>::new());
+        world.insert(WorldMsgQueue::<DeferredCommand>::new());
+        let entity = world.create_entity().with(StateMachine::new(Door::Closed)).with(Counter(0)).build();
+        let mut system = StateMachineSystem::<Door>::default();
+        system.run_now(&world);
+        world.fetch::<WorldMsgQueue<TransitionRequest<Door>>>().push(TransitionRequest(entity, Door::Opening));
+        for _ in 0..3 {
+            system.run_now(&world);
+        }
+        ApplyDeferredCommands::execute(&mut world, ());
+
+        let machines = world.read_storage::<StateMachine<Door>>();
+        let machine = machines.get(entity).unwrap();
+        assert_eq!(machine.current(), Door::Open);
+        let history: Vec<(Door, Door)> = machine.history().map(|t| (t.from, t.to)).collect();
+        assert_eq!(history, vec![(Door::Closed, Door::Opening), (Door::Opening, Door::Open)]);
+        assert_eq!(world.read_storage::<CurrentState>().get(entity).unwrap().name, "open");
+        assert_eq!(world.read_storage::<Counter>().get(entity).unwrap().0, 3);
+    }
+
+    #[test]
+    pub fn test_transition_before_first_update() {
+        let mut world = World::new();
+        crate::register_components(&mut world);
+        world.register::<StateMachine<Door>>();
+        world.register::<ExitCount>();
+        world.insert(Time { delta: 0.5, total: 0.0 });
+        world.insert(WorldMsgQueue::<TransitionRequest<Door>>::new());
+        world.insert(WorldMsgQueue::<DeferredCommand>::new());
+        let entity = world.create_entity().with(StateMachine::new(Door::Closed)).with(Counter(0)).with(ExitCount(0)).build();
+        world.fetch::<WorldMsgQueue<TransitionRequest<Door>>>().push(TransitionRequest(entity, Door::Opening));
+        StateMachineSystem::<Door>::default().run_now(&world);
+        ApplyDeferredCommands::execute(&mut world, ());
+
+        // The closed state was never entered so it is not exited, and the opening state is only entered once.
+        assert_eq!(world.read_storage::<StateMachine<Door>>().get(entity).unwrap().current(), Door::Opening);
+        assert_eq!(world.read_storage::<Counter>().get(entity).unwrap().0, 1);
+        assert_eq!(world.read_storage::<ExitCount>().get(entity).unwrap().0, 0);
+    }
+}
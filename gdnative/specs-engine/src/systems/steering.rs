@@ -0,0 +1,290 @@
+//! This module contains the steering behaviour systems. Each behaviour calculates a force that is added to the `Steering` component and
+//! the `ApplySteeringSystem` then applies the total force to the `Velocity`. The `ApplySteeringSystem` should run after all of the behaviours.
+//! Reference: https://www.red3d.com/cwr/steer/gdc99/
+use specs::prelude::*;
+use crate::components::*;
+use crate::resources::*;
+
+fn length(x: f32, y: f32) -> f32 {
+    (x * x + y * y).sqrt()
+}
+
+/// Scales the vector down so that its length does not exceed `max`.
+pub fn truncate(x: f32, y: f32, max: f32) -> (f32, f32) {
+    let len = length(x, y);
+    if len > max && len > 0.0 {
+        (x / len * max, y / len * max)
+    } else {
+        (x, y)
+    }
+}
+
+/// Returns the force required to change the velocity into one that heads towards the target at `speed`.
+fn steer_towards(position: &Position, velocity: &Velocity, target_x: f32, target_y: f32, speed: f32) -> (f32, f32) {
+    let (dx, dy) = (target_x - position.x, target_y - position.y);
+    let distance = length(dx, dy);
+    if distance <= f32::EPSILON {
+        return (-velocity.x, -velocity.y);
+    }
+    (dx / distance * speed - velocity.x, dy / distance * speed - velocity.y)
+}
+
+/// Calculates the `Seek`, `Flee` and `Arrive` behaviours.
+pub struct SeekFleeArriveSystem {}
+
+impl <'a> System <'a> for SeekFleeArriveSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, Velocity>,
+        ReadStorage<'a, Seek>,
+        ReadStorage<'a, Flee>,
+        ReadStorage<'a, Arrive>,
+        WriteStorage<'a, Steering>,
+    );
+    fn run(&mut self, data: Self::SystemData) {
+        let (positions, velocities, seeks, flees, arrives, mut steerings) = data;
+        (&positions, &velocities, &mut steerings, seeks.maybe(), flees.maybe(), arrives.maybe())
+            .par_join()
+            .for_each(|(position, velocity, steering, seek, flee, arrive)| {
+                if let Some(seek) = seek {
+                    let (x, y) = steer_towards(position, velocity, seek.x, seek.y, steering.max_speed);
+                    steering.add_force(x, y);
+                }
+                if let Some(flee) = flee {
+                    let distance = length(position.x - flee.x, position.y - flee.y);
+                    if flee.panic_distance.is_none_or(|panic_distance| distance < panic_distance) {
+                        // Fleeing is seeking towards the point mirrored on the other side of the entity.
+                        let (x, y) = steer_towards(position, velocity, 2.0 * position.x - flee.x, 2.0 * position.y - flee.y, steering.max_speed);
+                        steering.add_force(x, y);
+                    }
+                }
+                if let Some(arrive) = arrive {
+                    let distance = length(arrive.x - position.x, arrive.y - position.y);
+                    let speed = if distance < arrive.slowing_radius {
+                        steering.max_speed * distance / arrive.slowing_radius
+                    } else {
+                        steering.max_speed
+                    };
+                    let (x, y) = steer_towards(position, velocity, arrive.x, arrive.y, speed);
+                    steering.add_force(x, y);
+                }
+            });
+    }
+}
+
+/// Calculates the `Wander` behaviour.
+pub struct WanderSystem {}
+
+impl <'a> System <'a> for WanderSystem {
+    type SystemData = (
+        ReadExpect<'a, Time>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, Velocity>,
+        WriteStorage<'a, Wander>,
+        WriteStorage<'a, Steering>,
+    );
+    fn run(&mut self, data: Self::SystemData) {
+        let (time, positions, velocities, mut wanders, mut steerings) = data;
+        (&positions, &velocities, &mut wanders, &mut steerings)
+            .par_join()
+            .for_each(|(position, velocity, wander, steering)| {
+                wander.angle += wander.next_random() * wander.jitter * time.delta;
+                // The circle is projected along the current heading, entities that are not moving head to the right.
+                let speed = length(velocity.x, velocity.y);
+                let (heading_x, heading_y) = if speed > f32::EPSILON { (velocity.x / speed, velocity.y / speed) } else { (1.0, 0.0) };
+                let target_x = position.x + heading_x * wander.distance + wander.angle.cos() * wander.radius;
+                let target_y = position.y + heading_y * wander.distance + wander.angle.sin() * wander.radius;
+                let (x, y) = steer_towards(position, velocity, target_x, target_y, steering.max_speed);
+                steering.add_force(x, y);
+            });
+    }
+}
+
+/// Calculates the `Separation`, `Alignment` and `Cohesion` boid behaviours using the neighbours in the `SpatialGrid`.
+// Note: The `SpatialGrid` resource MUST be added to the simulation and updated before this system runs.
+pub struct FlockingSystem {}
+
+impl <'a> System <'a> for FlockingSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, SpatialGrid>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, Velocity>,
+        ReadStorage<'a, Separation>,
+        ReadStorage<'a, Alignment>,
+        ReadStorage<'a, Cohesion>,
+        WriteStorage<'a, Steering>,
+    );
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, grid, positions, velocities, separations, alignments, cohesions, mut steerings) = data;
+        (&entities, &positions, &velocities, &mut steerings, separations.maybe(), alignments.maybe(), cohesions.maybe())
+            .par_join()
+            .for_each(|(entity, position, velocity, steering, separation, alignment, cohesion)| {
+                let radius = separation.map_or(0.0, |s| s.radius)
+                    .max(alignment.map_or(0.0, |a| a.radius))
+                    .max(cohesion.map_or(0.0, |c| c.radius));
+                if radius <= 0.0 {
+                    return;
+                }
+                let mut separation_force = (0.0, 0.0);
+                let mut heading = (0.0, 0.0, 0);
+                let mut center = (0.0, 0.0, 0);
+                grid.for_each_in_radius(position.x, position.y, radius, |neighbour| {
+                    if neighbour.entity == entity {
+                        return;
+                    }
+                    let (dx, dy) = (position.x - neighbour.x, position.y - neighbour.y);
+                    let distance = length(dx, dy);
+                    if let Some(separation) = separation {
+                        if distance < separation.radius && distance > f32::EPSILON {
+                            // Dividing by the distance twice normalizes the direction and weights it by how close the neighbour is.
+                            separation_force.0 += dx / (distance * distance);
+                            separation_force.1 += dy / (distance * distance);
+                        }
+                    }
+                    if let Some(alignment) = alignment {
+                        if distance < alignment.radius {
+                            if let Some(neighbour_velocity) = velocities.get(neighbour.entity) {
+                                heading.0 += neighbour_velocity.x;
+                                heading.1 += neighbour_velocity.y;
+                                heading.2 += 1;
+                            }
+                        }
+                    }
+                    if let Some(cohesion) = cohesion {
+                        if distance < cohesion.radius {
+                            center.0 += neighbour.x;
+                            center.1 += neighbour.y;
+                            center.2 += 1;
+                        }
+                    }
+                });
+                if let Some(separation) = separation {
+                    let len = length(separation_force.0, separation_force.1);
+                    if len > f32::EPSILON {
+                        let desired = (separation_force.0 / len * steering.max_speed, separation_force.1 / len * steering.max_speed);
+                        steering.add_force((desired.0 - velocity.x) * separation.weight, (desired.1 - velocity.y) * separation.weight);
+                    }
+                }
+                if let (Some(alignment), true) = (alignment, heading.2 > 0) {
+                    let average = (heading.0 / heading.2 as f32, heading.1 / heading.2 as f32);
+                    steering.add_force((average.0 - velocity.x) * alignment.weight, (average.1 - velocity.y) * alignment.weight);
+                }
+                if let (Some(cohesion), true) = (cohesion, center.2 > 0) {
+                    let (x, y) = steer_towards(position, velocity, center.0 / center.2 as f32, center.1 / center.2 as f32, steering.max_speed);
+                    steering.add_force(x * cohesion.weight, y * cohesion.weight);
+                }
+            });
+    }
+}
+
+/// Applies the accumulated steering force to the `Velocity` and then resets it for the next frame.
+pub struct ApplySteeringSystem {}
+
+impl <'a> System <'a> for ApplySteeringSystem {
+    type SystemData = (
+        ReadExpect<'a, Time>,
+        WriteStorage<'a, Steering>,
+        WriteStorage<'a, Velocity>,
+    );
+    fn run(&mut self, data: Self::SystemData) {
+        let (time, mut steerings, mut velocities) = data;
+        (&mut steerings, &mut velocities)
+            .par_join()
+            .for_each(|(steering, velocity)| {
+                let (force_x, force_y) = truncate(steering.force.0, steering.force.1, steering.max_force);
+                let (x, y) = truncate(velocity.x + force_x * time.delta, velocity.y + force_y * time.delta, steering.max_speed);
+                velocity.x = x;
+                velocity.y = y;
+                steering.force = (0.0, 0.0);
+            });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn create_world() -> World {
+        let mut world = World::new();
+        crate::register_components(&mut world);
+        world.insert(Time { delta: 0.1, total: 0.0 });
+        world
+    }
+
+    #[test]
+    pub fn test_seek_respects_limits() {
+        let mut world = create_world();
+        let entity = world.create_entity()
+            .with(Position { x: 0.0, y: 0.0 })
+            .with(Velocity { x: 0.0, y: 0.0 })
+            .with(Steering::new(10.0, 5.0))
+            .with(Seek { x: 100.0, y: 0.0 })
+            .build();
+        SeekFleeArriveSystem {}.run_now(&world);
+        ApplySteeringSystem {}.run_now(&world);
+        {
+            // The desired change is 10, but the force is limited to 5 per second so after 0.1 seconds the speed is only 0.5.
+            let velocities = world.read_storage::<Velocity>();
+            let velocity = velocities.get(entity).unwrap();
+            assert!((velocity.x - 0.5).abs() < 0.001);
+            assert!(velocity.y.abs() < 0.001);
+        }
+        for _ in 0..100 {
+            SeekFleeArriveSystem {}.run_now(&world);
+            ApplySteeringSystem {}.run_now(&world);
+        }
+        let velocities = world.read_storage::<Velocity>();
+        let velocity = velocities.get(entity).unwrap();
+        assert!(length(velocity.x, velocity.y) <= 10.0 + 0.001);
+    }
+
+    #[test]
+    pub fn test_arrive_slows_down() {
+        let mut world = create_world();
+        let near = world.create_entity()
+            .with(Position { x: 0.0, y: 0.0 })
+            .with(Velocity { x: 0.0, y: 0.0 })
+            .with(Steering::new(10.0, 100.0))
+            .with(Arrive { x: 5.0, y: 0.0, slowing_radius: 10.0 })
+            .build();
+        let far = world.create_entity()
+            .with(Position { x: 0.0, y: 0.0 })
+            .with(Velocity { x: 0.0, y: 0.0 })
+            .with(Steering::new(10.0, 100.0))
+            .with(Arrive { x: 50.0, y: 0.0, slowing_radius: 10.0 })
+            .build();
+        SeekFleeArriveSystem {}.run_now(&world);
+        let steerings = world.read_storage::<Steering>();
+        // Halfway into the slowing radius the desired speed is half of the maximum.
+        let force = steerings.get(near).unwrap().force;
+        assert!((force.0 - 5.0).abs() < 0.001 && force.1.abs() < 0.001);
+        let force = steerings.get(far).unwrap().force;
+        assert!((force.0 - 10.0).abs() < 0.001 && force.1.abs() < 0.001);
+    }
+
+    #[test]
+    pub fn test_separation_uses_neighbours() {
+        let mut world = create_world();
+        world.insert(SpatialGrid::new(4.0));
+        let mut create_boid = |x: f32| world.create_entity()
+            .with(Position { x, y: 0.0 })
+            .with(Velocity { x: 0.0, y: 0.0 })
+            .with(Steering::new(10.0, 100.0))
+            .with(Separation { radius: 5.0, weight: 1.0 })
+            .build();
+        let left = create_boid(0.0);
+        let right = create_boid(1.0);
+        let alone = create_boid(100.0);
+        crate::systems::UpdateSpatialGridSystem {}.run_now(&world);
+        FlockingSystem {}.run_now(&world);
+        let steerings = world.read_storage::<Steering>();
+        let force = steerings.get(left).unwrap().force;
+        assert!((force.0 + 10.0).abs() < 0.001 && force.1.abs() < 0.001);
+        let force = steerings.get(right).unwrap().force;
+        assert!((force.0 - 10.0).abs() < 0.001 && force.1.abs() < 0.001);
+        assert_eq!(steerings.get(alone).unwrap().force, (0.0, 0.0));
+    }
+}
@@ -0,0 +1,133 @@
+//! This module contains the systems used to advance timers and cooldowns.
+use specs::prelude::*;
+use crate::commands::DeferredCommand;
+use crate::components::*;
+use crate::resources::*;
+
+/// This event is emitted each time a `Timer` finishes. The second value is the tag of the timer.
+#[derive(Debug, Clone, Copy)]
+pub struct TimerFinished(pub Entity, pub &'static str);
+
+/// Advances all `Timer` and `Cooldown` components.
+// Note: Both the `WorldMsgQueue<TimerFinished>` and `WorldMsgQueue<DeferredCommand>` resources MUST be added to the simulation for this system to work.
+pub struct TimerSystem {}
+
+impl <'a> System <'a> for TimerSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Time>,
+        ReadExpect<'a, WorldMsgQueue<TimerFinished>>,
+        ReadExpect<'a, WorldMsgQueue<DeferredCommand>>,
+        WriteStorage<'a, Timer>,
+        WriteStorage<'a, Cooldown>,
+    );
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, time, finished_queue, command_queue, mut timers, mut cooldowns) = data;
+        (&entities, &mut timers).par_join().for_each(|(entity, timer)| {
+            if timer.finished {
+                return;
+            }
+            timer.elapsed += time.delta;
+            // A repeating timer finishes once for every whole duration that has elapsed, even if that is more than once a frame.
+            while timer.elapsed >= timer.duration {
+                finished_queue.push(TimerFinished(entity, timer.tag));
+                if let Some(on_finished) = timer.on_finished {
+                    command_queue.push(on_finished(entity));
+                }
+                if !timer.repeat {
+                    timer.finished = true;
+                    break;
+                }
+                if timer.duration <= 0.0 {
+                    // Otherwise this would never stop, so a timer without a duration only finishes once per frame.
+                    timer.elapsed = 0.0;
+                    break;
+                }
+                timer.elapsed -= timer.duration;
+            }
+        });
+        (&mut cooldowns).par_join().for_each(|cooldown| {
+            cooldown.remaining = (cooldown.remaining - time.delta).max(0.0);
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::commands::{ApplyDeferredCommands, DespawnEntity};
+    use crate::systems::DespawnSystem;
+    use crate::util::WorldCommand;
+
+    fn create_world() -> World {
+        let mut world = World::new();
+        crate::register_components(&mut world);
+        world.insert(Time { delta: 0.5, total: 0.0 });
+        world.insert(WorldMsgQueue::<TimerFinished>::new());
+        world.insert(WorldMsgQueue::<DeferredCommand>::new());
+        world
+    }
+
+    #[test]
+    pub fn test_timer_finishes_once() {
+        let mut world = create_world();
+        let entity = world.create_entity().with(Timer::new(1.0, "once")).build();
+        let mut system = TimerSystem {};
+        for _ in 0..4 {
+            system.run_now(&world);
+        }
+        let queue = world.fetch::<WorldMsgQueue<TimerFinished>>();
+        let event = queue.pop().expect("the timer should have finished");
+        assert_eq!(event.0, entity);
+        assert_eq!(event.1, "once");
+        assert!(queue.pop().is_none());
+    }
+
+    #[test]
+    pub fn test_repeating_timer() {
+        let mut world = create_world();
+        world.create_entity().with(Timer::repeating(1.0, "repeat")).build();
+        let mut system = TimerSystem {};
+        for _ in 0..4 {
+            system.run_now(&world);
+        }
+        let queue = world.fetch::<WorldMsgQueue<TimerFinished>>();
+        assert!(queue.pop().is_some());
+        assert!(queue.pop().is_some());
+        assert!(queue.pop().is_none());
+    }
+
+    #[test]
+    pub fn test_repeating_timer_catches_up() {
+        let mut world = create_world();
+        world.create_entity().with(Timer::repeating(0.2, "fast")).build();
+        TimerSystem {}.run_now(&world);
+        let queue = world.fetch::<WorldMsgQueue<TimerFinished>>();
+        assert!(queue.pop().is_some());
+        assert!(queue.pop().is_some());
+        assert!(queue.pop().is_none());
+    }
+
+    #[test]
+    pub fn test_timer_command() {
+        let mut world = create_world();
+        let entity = world.create_entity().with(Timer::new(0.5, "despawn").with_command::<DespawnEntity>()).build();
+        TimerSystem {}.run_now(&world);
+        assert_eq!(ApplyDeferredCommands::execute(&mut world, ()), 1);
+        DespawnSystem {}.run_now(&world);
+        world.maintain();
+        assert!(!world.is_alive(entity));
+    }
+
+    #[test]
+    pub fn test_cooldown() {
+        let mut world = create_world();
+        let entity = world.create_entity().with(Cooldown::new(1.0)).build();
+        assert!(world.write_storage::<Cooldown>().get_mut(entity).unwrap().trigger());
+        assert!(!world.write_storage::<Cooldown>().get_mut(entity).unwrap().trigger());
+        let mut system = TimerSystem {};
+        system.run_now(&world);
+        system.run_now(&world);
+        assert!(world.read_storage::<Cooldown>().get(entity).unwrap().is_ready());
+    }
+}
@@ -0,0 +1,97 @@
+//! This module contains the systems used to animate components with tweens.
+use specs::prelude::*;
+use crate::components::*;
+use crate::resources::*;
+
+/// This event is emitted each time a tween reaches the end of its chain. The second value is the tag of the tween.
+#[derive(Debug, Clone, Copy)]
+pub struct TweenFinished(pub Entity, pub &'static str);
+
+/// Applies `TransformTween`s to the `Position`, `Scale` and `Rotation` of the entity.
+/// If the entity does not have the targeted component, the step will still run but nothing will be changed.
+// Note: The `WorldMsgQueue<TweenFinished>` resource MUST be added to the simulation for this system to work.
+pub struct TransformTweenSystem {}
+
+impl <'a> System <'a> for TransformTweenSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Time>,
+        ReadExpect<'a, WorldMsgQueue<TweenFinished>>,
+        WriteStorage<'a, TransformTween>,
+        WriteStorage<'a, Position>,
+        WriteStorage<'a, Scale>,
+        WriteStorage<'a, Rotation>,
+    );
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, time, finished_queue, mut tweens, mut positions, mut scales, mut rotations) = data;
+        for (entity, tween) in (&entities, &mut tweens).join() {
+            let finished = tween.advance(time.delta, |property, t| {
+                match *property {
+                    TransformProperty::Position { from, to } => {
+                        if let Some(position) = positions.get_mut(entity) {
+                            position.x = lerp(from.0, to.0, t);
+                            position.y = lerp(from.1, to.1, t);
+                        }
+                    },
+                    TransformProperty::Scale { from, to } => {
+                        if let Some(scale) = scales.get_mut(entity) {
+                            scale.x = lerp(from.0, to.0, t);
+                            scale.y = lerp(from.1, to.1, t);
+                        }
+                    },
+                    TransformProperty::Rotation { from, to } => {
+                        if let Some(rotation) = rotations.get_mut(entity) {
+                            rotation.radians = lerp(from, to, t);
+                        }
+                    },
+                }
+            });
+            if finished {
+                finished_queue.push(TweenFinished(entity, tween.tag));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_transform_tween() {
+        let mut world = World::new();
+        crate::register_components(&mut world);
+        world.insert(Time { delta: 1.5, total: 0.0 });
+        world.insert(WorldMsgQueue::<TweenFinished>::new());
+        let tween = TransformTween::new(TransformProperty::Position { from: (0.0, 0.0), to: (10.0, 20.0) }, 1.0, Easing::Linear)
+            .then(TransformProperty::Scale { from: (1.0, 1.0), to: (2.0, 2.0) }, 1.0, Easing::Linear)
+            .then(TransformProperty::Rotation { from: 0.0, to: 1.0 }, 1.0, Easing::Linear)
+            .with_tag("grow");
+        let entity = world.create_entity()
+            .with(Position { x: 0.0, y: 0.0 })
+            .with(Scale { x: 1.0, y: 1.0 })
+            .with(Rotation { radians: 0.0 })
+            .with(tween)
+            .build();
+        let mut system = TransformTweenSystem {};
+        system.run_now(&world);
+        {
+            let position = world.read_storage::<Position>().get(entity).map(|p| (p.x, p.y));
+            let scale = world.read_storage::<Scale>().get(entity).map(|s| (s.x, s.y));
+            assert_eq!(position, Some((10.0, 20.0)));
+            assert_eq!(scale, Some((1.5, 1.5)));
+            assert_eq!(world.read_storage::<Rotation>().get(entity).map(|r| r.radians), Some(0.0));
+            assert!(world.fetch::<WorldMsgQueue<TweenFinished>>().pop().is_none());
+        }
+        system.run_now(&world);
+        let scale = world.read_storage::<Scale>().get(entity).map(|s| (s.x, s.y));
+        assert_eq!(scale, Some((2.0, 2.0)));
+        assert_eq!(world.read_storage::<Rotation>().get(entity).map(|r| r.radians), Some(1.0));
+        let queue = world.fetch::<WorldMsgQueue<TweenFinished>>();
+        let event = queue.pop().expect("the tween should have finished");
+        assert_eq!(event.0, entity);
+        assert_eq!(event.1, "grow");
+        assert!(queue.pop().is_none());
+    }
+}
@@ -0,0 +1,77 @@
+//! This module contains the system used to choose the actions of entities with a `UtilityAi`.
+use specs::prelude::*;
+use crate::components::*;
+
+/// Scores the `UtilityAi` of each entity whose interval has elapsed in parallel and updates its `Intent`.
+/// If no action scores above 0 the `Intent` is removed. Considerations need read access to the whole world, so this is not a `System`
+/// and must either be added with `DispatcherBuilder::with_thread_local` or run with `run_now`.
+pub struct UtilitySystem {}
+
+impl <'a> RunNow<'a> for UtilitySystem {
+    fn run_now(&mut self, world: &'a World) {
+        let entities = world.entities();
+        let chosen: Vec<(Entity, Option<(&'static str, f32)>)> = {
+            let mut utility_ais = world.write_storage::<UtilityAi>();
+            (&entities, &mut utility_ais)
+                .par_join()
+                .filter_map(|(entity, utility_ai)| {
+                    if utility_ai.frames_until_update > 0 {
+                        utility_ai.frames_until_update -= 1;
+                        return None;
+                    }
+                    utility_ai.frames_until_update = utility_ai.interval - 1;
+                    Some((entity, utility_ai.reasoner.choose(world, entity)))
+                })
+                .collect()
+        };
+        let mut intents = world.write_storage::<Intent>();
+        for (entity, choice) in chosen {
+            match choice {
+                Some((action, score)) => {
+                    intents.insert(entity, Intent { action, score }).expect("the entity should be alive");
+                },
+                None => {
+                    intents.remove(entity);
+                },
+            }
+        }
+    }
+    fn setup(&mut self, world: &mut World) {
+        world.register::<UtilityAi>();
+        world.register::<Intent>();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utility::*;
+
+    fn health(world: &World, entity: Entity) -> f32 {
+        world.read_storage::<Counter>().get(entity).map_or(0.0, |counter| counter.0 as f32)
+    }
+
+    #[test]
+    pub fn test_utility_ai() {
+        let mut world = World::new();
+        crate::register_components(&mut world);
+        let reasoner = UtilityReasoner::new()
+            .with(UtilityAction::new("wander", 0.5))
+            .with(UtilityAction::new("flee", 1.0).with(Consideration::new(health, 0.0, 100.0, ResponseCurve::Inverse)))
+            .into_shared();
+        let entity = world.create_entity().with(Counter(100)).with(UtilityAi::new(reasoner, 2)).build();
+        let mut system = UtilitySystem {};
+        system.run_now(&world);
+        assert_eq!(world.read_storage::<Intent>().get(entity).unwrap().action, "wander");
+
+        world.write_storage::<Counter>().get_mut(entity).unwrap().0 = 10;
+        // The entity only reconsiders every second frame.
+        system.run_now(&world);
+        assert_eq!(world.read_storage::<Intent>().get(entity).unwrap().action, "wander");
+        system.run_now(&world);
+        let intents = world.read_storage::<Intent>();
+        let intent = intents.get(entity).unwrap();
+        assert_eq!(intent.action, "flee");
+        assert!((intent.score - 0.9).abs() < 0.001);
+    }
+}
@@ -0,0 +1,120 @@
+//! This contains the scoring used by utility AI. Each `UtilityAction` is scored from its `Consideration`s and the `UtilitySystem` writes the
+//! action with the highest score into the entity's `Intent`, which other systems can then act on.
+use std::sync::Arc;
+use specs::prelude::*;
+
+/// Maps a normalized input between 0.0 and 1.0 onto a score between 0.0 and 1.0.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResponseCurve {
+    /// `slope * x + offset`
+    Linear { slope: f32, offset: f32 },
+    /// `x ^ exponent`, exponents above 1.0 only score highly near the top of the range.
+    Power { exponent: f32 },
+    /// An S curve that rises around `midpoint`, higher `steepness` makes the rise sharper.
+    Logistic { steepness: f32, midpoint: f32 },
+    /// 1.0 at or above the threshold and 0.0 below it.
+    Step { threshold: f32 },
+    /// `1 - x`
+    Inverse,
+}
+
+impl ResponseCurve {
+    pub fn evaluate(&self, x: f32) -> f32 {
+        let x = x.clamp(0.0, 1.0);
+        let score = match *self {
+            ResponseCurve::Linear { slope, offset } => slope * x + offset,
+            ResponseCurve::Power { exponent } => x.powf(exponent),
+            ResponseCurve::Logistic { steepness, midpoint } => 1.0 / (1.0 + (-steepness * (x - midpoint)).exp()),
+            ResponseCurve::Step { threshold } => if x >= threshold { 1.0 } else { 0.0 },
+            ResponseCurve::Inverse => 1.0 - x,
+        };
+        score.clamp(0.0, 1.0)
+    }
+}
+
+/// Reads a raw value for the entity from its components or resources.
+/// The world is shared with every other entity that is being scored in parallel, so the `UtilityAi` storage MUST NOT be fetched.
+pub type UtilityInput = fn(&World, Entity) -> f32;
+
+/// A single input that is normalized from `min..=max` and then mapped through the curve.
+#[derive(Debug, Clone, Copy)]
+pub struct Consideration {
+    pub input: UtilityInput,
+    pub min: f32,
+    pub max: f32,
+    pub curve: ResponseCurve,
+}
+
+impl Consideration {
+    pub fn new(input: UtilityInput, min: f32, max: f32, curve: ResponseCurve) -> Self {
+        assert!(max > min);
+        Self { input, min, max, curve }
+    }
+    pub fn score(&self, world: &World, entity: Entity) -> f32 {
+        let value = (self.input)(world, entity);
+        self.curve.evaluate((value - self.min) / (self.max - self.min))
+    }
+}
+
+/// An action that an entity can choose. The score is the product of all of its considerations, multiplied by the weight.
+#[derive(Debug, Clone)]
+pub struct UtilityAction {
+    pub name: &'static str,
+    pub weight: f32,
+    pub considerations: Vec<Consideration>,
+}
+
+impl UtilityAction {
+    pub fn new(name: &'static str, weight: f32) -> Self {
+        Self { name, weight, considerations: Vec::new() }
+    }
+    pub fn with(mut self, consideration: Consideration) -> Self {
+        self.considerations.push(consideration);
+        self
+    }
+    /// Multiplying many scores together makes actions with more considerations score lower, so each score is compensated for the
+    /// number of considerations. An action without considerations scores its weight.
+    pub fn score(&self, world: &World, entity: Entity) -> f32 {
+        let compensation = 1.0 - 1.0 / self.considerations.len().max(1) as f32;
+        let mut total = 1.0;
+        for consideration in self.considerations.iter() {
+            let score = consideration.score(world, entity);
+            total *= score + (1.0 - score) * compensation * score;
+            if total <= 0.0 {
+                return 0.0;
+            }
+        }
+        total * self.weight
+    }
+}
+
+/// A set of actions shared by every entity that decides in the same way.
+#[derive(Debug, Clone, Default)]
+pub struct UtilityReasoner {
+    pub actions: Vec<UtilityAction>,
+}
+
+impl UtilityReasoner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn with(mut self, action: UtilityAction) -> Self {
+        self.actions.push(action);
+        self
+    }
+    /// Returns the action with the highest score and its score, ties go to the action that was added first.
+    /// Returns `None` if there are no actions or every action scored 0.
+    pub fn choose(&self, world: &World, entity: Entity) -> Option<(&'static str, f32)> {
+        let mut best: Option<(&'static str, f32)> = None;
+        for action in self.actions.iter() {
+            let score = action.score(world, entity);
+            if score > 0.0 && best.is_none_or(|(_, best_score)| score > best_score) {
+                best = Some((action.name, score));
+            }
+        }
+        best
+    }
+    pub fn into_shared(self) -> Arc<Self> {
+        Arc::new(self)
+    }
+}